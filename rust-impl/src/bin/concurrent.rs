@@ -0,0 +1,154 @@
+extern crate datastructures;
+extern crate criterion;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use criterion::Criterion;
+
+use datastructures::queues::concurrent::ConcurrentQueue;
+use datastructures::queues::concurrent::hazard::HazardPointers;
+use datastructures::queues::concurrent::lock_free::{HazardMsQueue, MsQueue};
+use datastructures::queues::concurrent::mpmc::{MpmcArrayQueue, PaddedMpmcArrayQueue, SegmentedMpmcQueue};
+use datastructures::queues::concurrent::padding::{CacheLine, NoPadding};
+
+const ITEMS: usize = 1 << 16;
+const CAPACITY: usize = 1 << 10;
+
+#[test]
+fn mutex_vec_deque_baseline() {
+    let input = generate_threads(0, 4);
+    Criterion::default()
+        .bench_function_over_inputs(
+            "mutex-vec-deque-baseline",
+            |b, &&threads| {
+                let queue = Arc::new(MutexVecDeque(Mutex::new(VecDeque::with_capacity(CAPACITY))));
+                b.iter(|| publish_subscribe(queue.clone(), threads, threads, ITEMS));
+            },
+            &input,
+        );
+}
+
+#[test]
+fn mpmc_array_queue() {
+    let input = generate_threads(0, 4);
+    Criterion::default()
+        .bench_function_over_inputs(
+            "mpmc-array-queue",
+            |b, &&threads| {
+                let queue = Arc::new(MpmcArrayQueue::new(CAPACITY));
+                b.iter(|| publish_subscribe(queue.clone(), threads, threads, ITEMS));
+            },
+            &input,
+        );
+}
+
+#[test]
+fn padded_mpmc_array_queue() {
+    let input = generate_threads(0, 4);
+    Criterion::default()
+        .bench_function_over_inputs(
+            "padded-mpmc-array-queue",
+            |b, &&threads| {
+                let queue: Arc<PaddedMpmcArrayQueue<usize>> = Arc::new(MpmcArrayQueue::with_padding(CAPACITY, CacheLine));
+                b.iter(|| publish_subscribe(queue.clone(), threads, threads, ITEMS));
+            },
+            &input,
+        );
+}
+
+#[test]
+fn segmented_mpmc_queue() {
+    let input = generate_threads(0, 4);
+    Criterion::default()
+        .bench_function_over_inputs(
+            "segmented-mpmc-queue",
+            |b, &&threads| {
+                let queue = Arc::new(SegmentedMpmcQueue::new(CAPACITY));
+                b.iter(|| publish_subscribe(queue.clone(), threads, threads, ITEMS));
+            },
+            &input,
+        );
+}
+
+#[test]
+fn ms_queue_epoch() {
+    let input = generate_threads(0, 4);
+    Criterion::default()
+        .bench_function_over_inputs(
+            "ms-queue-epoch",
+            |b, &&threads| {
+                let queue = Arc::new(MsQueue::new());
+                b.iter(|| publish_subscribe(queue.clone(), threads, threads, ITEMS));
+            },
+            &input,
+        );
+}
+
+#[test]
+fn ms_queue_hazard_pointers() {
+    let input = generate_threads(0, 4);
+    Criterion::default()
+        .bench_function_over_inputs(
+            "ms-queue-hazard-pointers",
+            |b, &&threads| {
+                let queue: Arc<HazardMsQueue<usize>> = Arc::new(MsQueue::with_reclaimer(NoPadding, HazardPointers));
+                b.iter(|| publish_subscribe(queue.clone(), threads, threads, ITEMS));
+            },
+            &input,
+        );
+}
+
+struct MutexVecDeque<T>(Mutex<VecDeque<T>>);
+
+impl <T> ConcurrentQueue<T> for MutexVecDeque<T> {
+    fn enqueue(&self, item: T) {
+        self.0.lock().unwrap().push_back(item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        self.0.lock().unwrap().pop_front()
+    }
+}
+
+// moves items from writers to readers, returns the sum of what readers got
+fn publish_subscribe<Q>(queue: Arc<Q>, readers: usize, writers: usize, items: usize) -> usize
+    where Q: ConcurrentQueue<usize> + Send + Sync + 'static {
+    let per_writer = items / writers;
+    let per_reader = per_writer * writers / readers;
+    let writer_handles = (0..writers).map(|_| {
+        let queue = queue.clone();
+        thread::spawn(move || {
+            for item in 0..per_writer {
+                queue.enqueue(item);
+            }
+        })
+    }).collect::<Vec<_>>();
+    let reader_handles = (0..readers).map(|_| {
+        let queue = queue.clone();
+        thread::spawn(move || {
+            let mut sum = 0;
+            let mut left = per_reader;
+            while left > 0 {
+                match queue.deque() {
+                    Some(item) => {
+                        sum += item;
+                        left -= 1;
+                    }
+                    None => thread::yield_now()
+                }
+            }
+            sum
+        })
+    }).collect::<Vec<_>>();
+    for handle in writer_handles {
+        handle.join().unwrap();
+    }
+    reader_handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+}
+
+fn generate_threads(min_power: u32, max_power: u32) -> Vec<usize> {
+    (min_power..max_power).map(|power| 2usize.pow(power))
+        .collect::<Vec<usize>>()
+}
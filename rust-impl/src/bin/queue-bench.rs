@@ -0,0 +1,206 @@
+extern crate datastructures;
+extern crate serde_json;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use datastructures::bench::{Config, Error, Measurement};
+use datastructures::bench::chart;
+use datastructures::bench::jmh::{self, Mode, TimeUnit};
+use datastructures::bench::payload::{PayloadSize, PAYLOAD_SIZES};
+use datastructures::bench::registry::{self, Entry};
+use datastructures::bench::report::{self, Format, Score};
+use datastructures::bench::sizes::Strategy;
+use datastructures::queues::sequential::MIN_SEGMENT_CAPACITY;
+
+const USAGE: &'static str = "usage: queue-bench [options]
+       queue-bench report [report options]
+
+options:
+    --queue NAME           queue to run, repeat to run several, all queues by default
+    --sizes SPEC           size strategy such as pow2:10..26, step:13..20/1024, geo:1024..65536*1.5,
+                           cache:64, rand:1024..65536x16@42 or several joined with +, pow2:10..20 by default
+    --payload LIST         comma separated item sizes as BYTES or BYTES@ALIGN, such as 8,64,64@64,4096@4096,
+                           8 by default
+    --segment-size N       items per segment of segmented queues, 256 by default
+    --warmup N             round trips before measuring, 5 by default
+    --iterations N         measured round trips, 10 by default
+    --result FILE          write results to FILE in the JMH JSON format
+    --mode MODE            JMH mode of the written results, thrpt, avgt or ss, avgt by default
+    --time-unit UNIT       time unit of the written results, ns, us, ms or s, us by default
+    --list                 print registered queues and payloads and exit
+
+report options:
+    --rust FILE            results written with --result, repeat to read several
+    --java FILE            JMH JSON results of the Java benchmarks, repeat to read several
+    --out DIR              directory for the report and its SVG charts, target/reports/queues by default
+    --format FORMAT        md or html, md by default";
+
+struct Output {
+    path: Option<String>,
+    mode: Mode,
+    unit: TimeUnit
+}
+
+struct Report {
+    rust: Vec<String>,
+    java: Vec<String>,
+    out: String,
+    format: Format
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    let result = if args.peek().map_or(false, |arg| arg == "report") {
+        args.next();
+        parse_report(args).and_then(|report| write_report(&report))
+    } else {
+        parse(args).and_then(|(queues, config, output)| run(&queues, &config, &output))
+    };
+    match result {
+        Ok(()) => {}
+        Err(error) => {
+            eprintln!("queue-bench: {}\n\n{}", error, USAGE);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<(Vec<Entry>, Config, Output), Error> {
+    let mut queues = vec![];
+    let mut config = Config::default();
+    let mut output = Output { path: None, mode: Mode::AverageTime, unit: TimeUnit::Microseconds };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => {
+                for entry in registry::entries() {
+                    println!("{}{}", entry.name, if entry.segmented { " (segmented)" } else { "" });
+                }
+                let payloads = PAYLOAD_SIZES.iter().map(|size| size.to_string()).collect::<Vec<_>>();
+                println!("\npayloads: {}", payloads.join(", "));
+                process::exit(0);
+            }
+            "--queue" => queues.push(registry::find(&value(&arg, args.next())?)?),
+            "--sizes" => config.sizes = value(&arg, args.next())?.parse::<Strategy>()?.sizes(),
+            "--payload" => config.payloads = value(&arg, args.next())?.split(',')
+                .map(str::parse::<PayloadSize>)
+                .collect::<Result<Vec<_>, _>>()?,
+            "--segment-size" => config.segment_size = number(&arg, args.next())?,
+            "--warmup" => config.warmup = number(&arg, args.next())?,
+            "--iterations" => config.iterations = number(&arg, args.next())?,
+            "--result" => output.path = Some(value(&arg, args.next())?),
+            "--mode" => output.mode = value(&arg, args.next())?.parse()?,
+            "--time-unit" => output.unit = value(&arg, args.next())?.parse()?,
+            _ => return Err(Error::InvalidArgument(format!("unknown option `{}`", arg)))
+        }
+    }
+    if config.iterations == 0 {
+        return Err(Error::InvalidArgument("`--iterations` must be at least 1".to_owned()));
+    }
+    if config.segment_size < MIN_SEGMENT_CAPACITY {
+        return Err(Error::InvalidArgument(format!("`--segment-size` must be at least {}, got {}", MIN_SEGMENT_CAPACITY, config.segment_size)));
+    }
+    if queues.is_empty() {
+        queues = registry::entries();
+    }
+    Ok((queues, config, output))
+}
+
+fn parse_report<I: Iterator<Item = String>>(mut args: I) -> Result<Report, Error> {
+    let mut report = Report { rust: vec![], java: vec![], out: "target/reports/queues".to_owned(), format: Format::Markdown };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rust" => report.rust.push(value(&arg, args.next())?),
+            "--java" => report.java.push(value(&arg, args.next())?),
+            "--out" => report.out = value(&arg, args.next())?,
+            "--format" => report.format = value(&arg, args.next())?.parse()?,
+            _ => return Err(Error::InvalidArgument(format!("unknown report option `{}`", arg)))
+        }
+    }
+    if report.rust.is_empty() || report.java.is_empty() {
+        return Err(Error::InvalidArgument("a report needs both `--rust` and `--java` results".to_owned()));
+    }
+    Ok(report)
+}
+
+fn value(option: &str, value: Option<String>) -> Result<String, Error> {
+    value.ok_or_else(|| Error::InvalidArgument(format!("`{}` needs a value", option)))
+}
+
+fn number<T: FromStr>(option: &str, arg: Option<String>) -> Result<T, Error> {
+    let arg = value(option, arg)?;
+    arg.parse().map_err(|_| Error::InvalidArgument(format!("`{}` expects a number, got `{}`", option, arg)))
+}
+
+fn run(queues: &[Entry], config: &Config, output: &Output) -> Result<(), Error> {
+    let mut measurements = vec![];
+    println!("{:<40} {:>10} {:>10} {:>8} {:>12} {:>16}", "queue", "size", "payload", "segment", "ns/op", "ops/s");
+    for entry in queues {
+        for measurement in entry.run(config)? {
+            println!("{:<40} {:>10} {:>10} {:>8} {:>12.2} {:>16.0}",
+                measurement.queue,
+                measurement.size,
+                measurement.payload.to_string(),
+                measurement.segment_size.map_or("-".to_owned(), |segment| segment.to_string()),
+                measurement.nanos_per_operation(),
+                measurement.operations_per_second());
+            measurements.push(measurement);
+        }
+    }
+    match output.path {
+        Some(ref path) => write_results(path, &measurements, config, output),
+        None => Ok(())
+    }
+}
+
+fn write_results(path: &str, measurements: &[Measurement], config: &Config, output: &Output) -> Result<(), Error> {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| Error::Io(format!("cannot create `{}`: {}", parent.display(), error)))?;
+    }
+    write_file(path, &format!("{:#}\n", jmh::results(measurements, config, output.mode, output.unit)))
+}
+
+fn write_report(report: &Report) -> Result<(), Error> {
+    let sections = report::sections(&read_scores(&report.java)?, &read_scores(&report.rust)?);
+    if sections.is_empty() {
+        return Err(Error::InvalidResults("no comparison has results from both Rust and Java".to_owned()));
+    }
+    let out = Path::new(&report.out);
+    fs::create_dir_all(out).map_err(|error| Error::Io(format!("cannot create `{}`: {}", out.display(), error)))?;
+    for section in &sections {
+        write_file(&out.join(format!("{}.svg", section.slug())), &chart::svg(section))?;
+    }
+    let index = out.join(format!("index.{}", report.format.extension()));
+    write_file(&index, &report::render(&sections, report.format))?;
+    println!("compared {} pairs of implementations in {}", sections.len(), index.display());
+    Ok(())
+}
+
+fn read_scores(paths: &[String]) -> Result<Vec<Score>, Error> {
+    let mut scores = vec![];
+    for path in paths {
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|error| Error::Io(format!("cannot read `{}`: {}", path, error)))?;
+        let json = text.parse::<Value>()
+            .map_err(|error| Error::InvalidResults(format!("{}: invalid JSON: {}", path, error)))?;
+        let results = report::scores(&json)
+            .map_err(|error| Error::InvalidResults(format!("{}: {}", path, error)))?;
+        scores.extend(results);
+    }
+    Ok(scores)
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), Error> {
+    File::create(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|error| Error::Io(format!("cannot write `{}`: {}", path.display(), error)))
+}
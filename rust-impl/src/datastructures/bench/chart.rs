@@ -0,0 +1,162 @@
+use super::report::{escape, Language, Section};
+
+const WIDTH: f64 = 1040.0;
+const HEIGHT: f64 = 480.0;
+const LEFT: f64 = 80.0;
+const RIGHT: f64 = 400.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 60.0;
+
+const COLORS: [&'static str; 8] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf"];
+
+// throughput in millions of operations per second against the size on a log scale,
+// java series are dashed so that the languages tell apart without colors
+pub fn svg(section: &Section) -> String {
+    let sizes = section.sizes();
+    let (min, max) = match (sizes.first(), sizes.last()) {
+        (Some(&min), Some(&max)) => (min as f64, max as f64),
+        _ => (1.0, 1.0)
+    };
+    let top = nice_ceiling(section.series.iter()
+        .flat_map(|series| series.scores.iter().map(|score| score.throughput / 1e6))
+        .filter(|throughput| throughput.is_finite())
+        .fold(0.0, f64::max));
+    let plot_width = WIDTH - LEFT - RIGHT;
+    let plot_height = HEIGHT - TOP - BOTTOM;
+    let x = |size: usize| if max > min {
+        LEFT + plot_width * ((size as f64).ln() - min.ln()) / (max.ln() - min.ln())
+    } else {
+        LEFT + plot_width / 2.0
+    };
+    let y = |throughput: f64| TOP + plot_height * (1.0 - throughput / top);
+
+    let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" \
+        font-family=\"sans-serif\" font-size=\"12\">\n", WIDTH, HEIGHT, WIDTH, HEIGHT);
+    out.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", WIDTH, HEIGHT));
+    out.push_str(&format!("<text x=\"{}\" y=\"24\" font-size=\"16\">{}</text>\n", LEFT, escape(section.title)));
+
+    for step in 0..6 {
+        let throughput = top * step as f64 / 5.0;
+        out.push_str(&format!("<line x1=\"{}\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#ddd\"/>\n",
+            LEFT, y(throughput), LEFT + plot_width, y(throughput)));
+        out.push_str(&format!("<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n", LEFT - 8.0, y(throughput) + 4.0, throughput));
+    }
+    let every = (sizes.len() + 11) / 12;
+    for (index, &size) in sizes.iter().enumerate() {
+        if index % every != 0 {
+            continue;
+        }
+        out.push_str(&format!("<line x1=\"{:.1}\" y1=\"{}\" x2=\"{:.1}\" y2=\"{}\" stroke=\"#999\"/>\n",
+            x(size), TOP + plot_height, x(size), TOP + plot_height + 5.0));
+        out.push_str(&format!("<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n", x(size), TOP + plot_height + 20.0, size_label(size)));
+    }
+    out.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#999\"/>\n",
+        LEFT, TOP, plot_width, plot_height));
+    out.push_str(&format!("<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">queue size</text>\n", LEFT + plot_width / 2.0, HEIGHT - 16.0));
+    out.push_str(&format!("<text transform=\"translate(20 {}) rotate(-90)\" text-anchor=\"middle\">million operations per second</text>\n",
+        TOP + plot_height / 2.0));
+
+    for (index, series) in section.series.iter().enumerate() {
+        let color = COLORS[index % COLORS.len()];
+        let dash = match series.language {
+            Language::Java => " stroke-dasharray=\"6 4\"",
+            Language::Rust => ""
+        };
+        let points = series.scores.iter()
+            .filter(|score| score.throughput.is_finite())
+            .map(|score| (x(score.size), y(score.throughput / 1e6)))
+            .collect::<Vec<_>>();
+        let line = points.iter().map(|&(x, y)| format!("{:.1},{:.1}", x, y)).collect::<Vec<_>>();
+        out.push_str(&format!("<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"{}/>\n", line.join(" "), color, dash));
+        for &(x, y) in &points {
+            out.push_str(&format!("<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"/>\n", x, y, color));
+        }
+        let legend = TOP + 10.0 + 20.0 * index as f64;
+        out.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"2\"{}/>\n",
+            WIDTH - RIGHT + 20.0, legend, WIDTH - RIGHT + 50.0, legend, color, dash));
+        out.push_str(&format!("<text x=\"{}\" y=\"{}\">{}</text>\n", WIDTH - RIGHT + 58.0, legend + 4.0, escape(&series.label)));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+// rounds up to 1, 2 or 5 times a power of ten so that the grid lines get round labels
+fn nice_ceiling(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].iter()
+        .map(|step| step * magnitude)
+        .find(|&ceiling| ceiling >= value)
+        .unwrap_or(10.0 * magnitude)
+}
+
+pub fn size_label(size: usize) -> String {
+    const K: usize = 1024;
+    const M: usize = 1024 * 1024;
+    if size >= M && size % M == 0 {
+        format!("{}M", size / M)
+    } else if size >= K && size % K == 0 {
+        format!("{}K", size / K)
+    } else {
+        size.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod chart {
+        use super::super::{nice_ceiling, size_label, svg};
+        use super::super::super::report::{Language, Score, Section, Series};
+
+        fn series(label: &str, language: Language, points: Vec<(usize, f64)>) -> Series {
+            Series {
+                label: label.to_owned(),
+                language: language,
+                scores: points.into_iter()
+                    .map(|(size, throughput)| Score { benchmark: label.to_owned(), mode: "avgt".to_owned(), params: vec![], size: size, throughput: throughput, error: 0.0 })
+                    .collect()
+            }
+        }
+
+        #[test]
+        fn round_grid_ceilings() {
+            assert_eq!(nice_ceiling(0.0), 1.0);
+            assert_eq!(nice_ceiling(1.0), 1.0);
+            assert_eq!(nice_ceiling(130.0), 200.0);
+            assert_eq!(nice_ceiling(420.0), 500.0);
+            assert_eq!(nice_ceiling(501.0), 1000.0);
+        }
+
+        #[test]
+        fn abbreviate_sizes() {
+            assert_eq!(size_label(1000), "1000");
+            assert_eq!(size_label(1024), "1K");
+            assert_eq!(size_label(13 * 1024), "13K");
+            assert_eq!(size_label(32 * 1024 * 1024), "32M");
+        }
+
+        #[test]
+        fn line_per_series() {
+            let section = Section {
+                title: "Linked <queues>",
+                series: vec![
+                    series("java LinkedVsArray.linked_g1", Language::Java, vec![(1024, 100e6), (4096, 50e6)]),
+                    series("rust shared-linked-queue", Language::Rust, vec![(1024, 200e6), (2048, ::std::f64::NAN)])
+                ]
+            };
+
+            let svg = svg(&section);
+
+            assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+            assert!(svg.ends_with("</svg>\n"));
+            assert!(svg.contains("Linked &lt;queues&gt;"));
+            assert_eq!(svg.matches("<polyline").count(), 2);
+            assert_eq!(svg.matches("<circle").count(), 3);
+            assert_eq!(svg.matches("stroke-dasharray").count(), 2);
+            assert!(svg.contains("<polyline points=\"80.0,230.0 640.0,325.0\""));
+            assert!(svg.contains(">4K</text>"));
+        }
+    }
+}
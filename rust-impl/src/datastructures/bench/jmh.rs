@@ -0,0 +1,354 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::{Config, Error, Measurement};
+
+pub const BENCHMARK_PREFIX: &'static str = "datastructures.queue_bench.";
+
+const PERCENTILES: [(f64, &'static str); 10] = [
+    (0.0, "0.0"), (50.0, "50.0"), (90.0, "90.0"), (95.0, "95.0"), (99.0, "99.0"),
+    (99.9, "99.9"), (99.99, "99.99"), (99.999, "99.999"), (99.9999, "99.9999"), (100.0, "100.0")
+];
+
+// two sided 99.9% quantiles of the Student t distribution, JMH reports errors at that confidence
+const T_QUANTILES: [f64; 30] = [
+    636.619, 31.599, 12.924, 8.610, 6.869, 5.959, 5.408, 5.041, 4.781, 4.587,
+    4.437, 4.318, 4.221, 4.140, 4.073, 4.015, 3.965, 3.922, 3.883, 3.850,
+    3.819, 3.792, 3.768, 3.745, 3.725, 3.707, 3.690, 3.674, 3.659, 3.646
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Throughput,
+    AverageTime,
+    SingleShot
+}
+
+impl Mode {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            Mode::Throughput => "thrpt",
+            Mode::AverageTime => "avgt",
+            Mode::SingleShot => "ss"
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = Error;
+
+    fn from_str(label: &str) -> Result<Mode, Error> {
+        match label {
+            "thrpt" => Ok(Mode::Throughput),
+            "avgt" => Ok(Mode::AverageTime),
+            "ss" => Ok(Mode::SingleShot),
+            _ => Err(Error::InvalidArgument(format!("unknown mode `{}`, expected thrpt, avgt or ss", label)))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds
+}
+
+impl TimeUnit {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            TimeUnit::Nanoseconds => "ns",
+            TimeUnit::Microseconds => "us",
+            TimeUnit::Milliseconds => "ms",
+            TimeUnit::Seconds => "s"
+        }
+    }
+
+    pub(crate) fn nanos(&self) -> f64 {
+        match *self {
+            TimeUnit::Nanoseconds => 1.0,
+            TimeUnit::Microseconds => 1e3,
+            TimeUnit::Milliseconds => 1e6,
+            TimeUnit::Seconds => 1e9
+        }
+    }
+}
+
+impl FromStr for TimeUnit {
+    type Err = Error;
+
+    fn from_str(label: &str) -> Result<TimeUnit, Error> {
+        match label {
+            "ns" => Ok(TimeUnit::Nanoseconds),
+            "us" => Ok(TimeUnit::Microseconds),
+            "ms" => Ok(TimeUnit::Milliseconds),
+            "s" => Ok(TimeUnit::Seconds),
+            _ => Err(Error::InvalidArgument(format!("unknown time unit `{}`, expected ns, us, ms or s", label)))
+        }
+    }
+}
+
+// a JMH operation is one invocation, here a round trip of `size` enqueues and deques,
+// the same as an invocation of the Java QueueBenchmark subclasses
+pub fn scores(measurement: &Measurement, mode: Mode, unit: TimeUnit) -> Vec<f64> {
+    measurement.samples.iter()
+        .map(|&nanos| {
+            let time = nanos as f64 / unit.nanos();
+            match mode {
+                Mode::Throughput => 1.0 / time,
+                Mode::AverageTime | Mode::SingleShot => time
+            }
+        })
+        .collect()
+}
+
+pub fn score_unit(mode: Mode, unit: TimeUnit) -> String {
+    match mode {
+        Mode::Throughput => format!("ops/{}", unit.label()),
+        Mode::AverageTime | Mode::SingleShot => format!("{}/op", unit.label())
+    }
+}
+
+pub fn mean(scores: &[f64]) -> f64 {
+    if scores.is_empty() {
+        return ::std::f64::NAN;
+    }
+    scores.iter().sum::<f64>() / scores.len() as f64
+}
+
+// half width of the 99.9% confidence interval, NaN when a single score leaves nothing to compare
+pub fn score_error(scores: &[f64]) -> f64 {
+    let n = scores.len();
+    if n < 2 {
+        return ::std::f64::NAN;
+    }
+    let mean = mean(scores);
+    let variance = scores.iter().map(|score| (score - mean) * (score - mean)).sum::<f64>() / (n - 1) as f64;
+    t_quantile(n - 1) * variance.sqrt() / (n as f64).sqrt()
+}
+
+fn t_quantile(degrees: usize) -> f64 {
+    if degrees == 0 {
+        ::std::f64::NAN
+    } else if degrees <= T_QUANTILES.len() {
+        T_QUANTILES[degrees - 1]
+    } else if degrees <= 40 {
+        3.551
+    } else if degrees <= 60 {
+        3.460
+    } else if degrees <= 120 {
+        3.373
+    } else {
+        3.291
+    }
+}
+
+// estimates percentiles the way commons-math does for JMH, interpolating at p * (n + 1) / 100
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let n = sorted.len();
+    if n == 0 {
+        return ::std::f64::NAN;
+    }
+    let position = p * (n + 1) as f64 / 100.0;
+    if position < 1.0 {
+        return sorted[0];
+    }
+    if position >= n as f64 {
+        return sorted[n - 1];
+    }
+    let lower = position.floor() as usize;
+    let fraction = position - position.floor();
+    sorted[lower - 1] + fraction * (sorted[lower] - sorted[lower - 1])
+}
+
+// NaN sorts last instead of panicking, it only shows up in the highest percentiles
+fn sort_scores(scores: &mut [f64]) {
+    scores.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| a.is_nan().cmp(&b.is_nan())));
+}
+
+pub fn benchmark_name(queue: &str) -> String {
+    format!("{}{}", BENCHMARK_PREFIX, queue.replace('-', "_"))
+}
+
+pub fn result(measurement: &Measurement, config: &Config, mode: Mode, unit: TimeUnit) -> Value {
+    let scores = scores(measurement, mode, unit);
+    let mut sorted = scores.clone();
+    sort_scores(&mut sorted);
+    let score = mean(&scores);
+    let error = score_error(&scores);
+
+    let mut params = json!({
+        "size": measurement.size.to_string(),
+        "payload": measurement.payload.to_string()
+    });
+    if let Some(segment_size) = measurement.segment_size {
+        params["segmentSize"] = Value::String(segment_size.to_string());
+    }
+
+    // there is no JMH version and iterations are round trips rather than time spans, so those fields
+    // stay empty like the ones that describe the JVM instead of holding values JMH never writes
+    json!({
+        "jmhVersion": "",
+        "benchmark": benchmark_name(measurement.queue),
+        "mode": mode.label(),
+        "threads": 1,
+        "forks": 1,
+        "jvm": "",
+        "jvmArgs": [],
+        "jdkVersion": "",
+        "vmName": "rustc",
+        "vmVersion": "",
+        "warmupIterations": config.warmup,
+        "warmupTime": "",
+        "warmupBatchSize": 1,
+        "measurementIterations": config.iterations,
+        "measurementTime": "",
+        "measurementBatchSize": 1,
+        "params": params,
+        "primaryMetric": {
+            "score": number(score),
+            "scoreError": number(error),
+            "scoreConfidence": [number(score - error), number(score + error)],
+            "scorePercentiles": Value::Object(PERCENTILES.iter()
+                .map(|&(p, label)| (label.to_owned(), number(percentile(&sorted, p))))
+                .collect()),
+            "scoreUnit": score_unit(mode, unit),
+            "rawData": [scores.into_iter().map(number).collect::<Vec<_>>()]
+        },
+        "secondaryMetrics": {}
+    })
+}
+
+pub fn results(measurements: &[Measurement], config: &Config, mode: Mode, unit: TimeUnit) -> Value {
+    Value::Array(measurements.iter().map(|measurement| result(measurement, config, mode, unit)).collect())
+}
+
+// like JMH, numbers that JSON cannot hold are written as strings
+pub fn number(value: f64) -> Value {
+    if value.is_nan() {
+        Value::from("NaN")
+    } else if value.is_infinite() {
+        Value::from(if value > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        Value::from(value)
+    }
+}
+
+pub fn as_number(json: &Value) -> Option<f64> {
+    match *json {
+        Value::String(ref value) if value == "NaN" => Some(::std::f64::NAN),
+        Value::String(ref value) if value == "Infinity" => Some(::std::f64::INFINITY),
+        Value::String(ref value) if value == "-Infinity" => Some(::std::f64::NEG_INFINITY),
+        _ => json.as_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod jmh {
+        use super::super::{as_number, benchmark_name, number, percentile, result, score_error, scores, sort_scores, Mode, TimeUnit};
+        use super::super::super::{Config, Measurement};
+        use super::super::super::payload::PayloadSize;
+
+        fn measurement(samples: Vec<u64>) -> Measurement {
+            Measurement {
+                queue: "shared-link-linked-array-queue",
+                size: 1024,
+                payload: PayloadSize::aligned(64, 64),
+                segment_size: Some(256),
+                samples: samples
+            }
+        }
+
+        #[test]
+        fn convert_samples_to_scores() {
+            let measurement = measurement(vec![2000, 4000]);
+
+            assert_eq!(scores(&measurement, Mode::AverageTime, TimeUnit::Microseconds), vec![2.0, 4.0]);
+            assert_eq!(scores(&measurement, Mode::SingleShot, TimeUnit::Nanoseconds), vec![2000.0, 4000.0]);
+            assert_eq!(scores(&measurement, Mode::Throughput, TimeUnit::Milliseconds), vec![500.0, 250.0]);
+        }
+
+        #[test]
+        fn parse_modes_and_units() {
+            assert_eq!("thrpt".parse(), Ok(Mode::Throughput));
+            assert_eq!("avgt".parse(), Ok(Mode::AverageTime));
+            assert_eq!("ss".parse(), Ok(Mode::SingleShot));
+            assert!("sample".parse::<Mode>().is_err());
+            assert_eq!("us".parse(), Ok(TimeUnit::Microseconds));
+            assert!("min".parse::<TimeUnit>().is_err());
+        }
+
+        #[test]
+        fn error_is_half_of_confidence_interval() {
+            let error = score_error(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+            assert!((error - 8.610 * 2.5f64.sqrt() / 5f64.sqrt()).abs() < 1e-9);
+            assert!(score_error(&[1.0]).is_nan());
+        }
+
+        #[test]
+        fn interpolated_percentiles() {
+            let sorted = [1.0, 2.0, 3.0, 4.0];
+
+            assert_eq!(percentile(&sorted, 0.0), 1.0);
+            assert_eq!(percentile(&sorted, 50.0), 2.5);
+            assert_eq!(percentile(&sorted, 90.0), 4.0);
+            assert_eq!(percentile(&sorted, 100.0), 4.0);
+        }
+
+        #[test]
+        fn sort_nan_scores_last() {
+            let mut sorted = [3.0, ::std::f64::NAN, 1.0, ::std::f64::INFINITY, ::std::f64::NAN, 2.0];
+
+            sort_scores(&mut sorted);
+
+            assert_eq!(&sorted[..4], &[1.0, 2.0, 3.0, ::std::f64::INFINITY]);
+            assert!(sorted[4].is_nan() && sorted[5].is_nan());
+        }
+
+        #[test]
+        fn result_of_instant_round_trips() {
+            let json = result(&measurement(vec![0, 1000]), &Config::default(), Mode::Throughput, TimeUnit::Microseconds);
+
+            assert_eq!(json["primaryMetric"]["scorePercentiles"]["100.0"], "Infinity");
+        }
+
+        #[test]
+        fn numbers_json_cannot_hold_round_trip_as_strings() {
+            for &value in &[1.5, ::std::f64::INFINITY, ::std::f64::NEG_INFINITY] {
+                assert_eq!(as_number(&number(value)), Some(value));
+            }
+            assert_eq!(number(::std::f64::NAN), "NaN");
+            assert!(as_number(&number(::std::f64::NAN)).unwrap().is_nan());
+            assert_eq!(as_number(&json!("1.5")), None);
+        }
+
+        #[test]
+        fn result_follows_jmh_schema() {
+            let config = Config { warmup: 5, iterations: 3, ..Config::default() };
+
+            let json = result(&measurement(vec![1000, 2000, 3000]), &config, Mode::AverageTime, TimeUnit::Microseconds);
+
+            assert_eq!(json["benchmark"], benchmark_name("shared-link-linked-array-queue").as_str());
+            assert_eq!(json["mode"], "avgt");
+            assert_eq!(json["jmhVersion"], "");
+            assert_eq!(json["warmupTime"], "");
+            assert_eq!(json["measurementTime"], "");
+            assert_eq!(json["measurementIterations"], 3);
+            assert_eq!(json["params"], json!({"size": "1024", "payload": "64@64", "segmentSize": "256"}));
+            let metric = &json["primaryMetric"];
+            assert_eq!(metric["score"], 2.0);
+            assert_eq!(metric["scoreUnit"], "us/op");
+            assert_eq!(metric["scorePercentiles"]["50.0"], 2.0);
+            assert_eq!(metric["rawData"], json!([[1.0, 2.0, 3.0]]));
+        }
+
+        #[test]
+        fn benchmark_names_look_like_java_methods() {
+            assert_eq!(benchmark_name("std-vec-deque"), "datastructures.queue_bench.std_vec_deque");
+        }
+    }
+}
@@ -0,0 +1,161 @@
+pub mod chart;
+pub mod jmh;
+pub mod payload;
+pub mod registry;
+pub mod report;
+pub mod sizes;
+
+extern crate test;
+
+use self::test::black_box;
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use queues::Queue;
+
+use self::payload::{Payload, PayloadSize, PAYLOAD_SIZES};
+use self::sizes::Strategy;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub sizes: Vec<usize>,
+    pub payloads: Vec<PayloadSize>,
+    pub segment_size: usize,
+    pub warmup: usize,
+    pub iterations: usize
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            sizes: Strategy::powers(10, 20).sizes(),
+            payloads: vec![PayloadSize::new(8)],
+            segment_size: 256,
+            warmup: 5,
+            iterations: 10
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnknownQueue(String),
+    UnsupportedPayload(PayloadSize),
+    InvalidSizeSpec(String),
+    InvalidArgument(String),
+    Io(String),
+    InvalidResults(String)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::UnknownQueue(ref name) => write!(f, "unknown queue `{}`", name),
+            Error::UnsupportedPayload(size) => {
+                let supported = PAYLOAD_SIZES.iter().map(|size| size.to_string()).collect::<Vec<_>>();
+                write!(f, "unsupported payload `{}`, expected one of {}", size, supported.join(", "))
+            }
+            Error::InvalidSizeSpec(ref spec) => write!(f, "invalid size spec `{}`", spec),
+            Error::InvalidArgument(ref message) | Error::Io(ref message) | Error::InvalidResults(ref message) => write!(f, "{}", message)
+        }
+    }
+}
+
+// samples are nanoseconds per round trip of `size` enqueues followed by `size` deques
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub queue: &'static str,
+    pub size: usize,
+    pub payload: PayloadSize,
+    pub segment_size: Option<usize>,
+    pub samples: Vec<u64>
+}
+
+impl Measurement {
+    pub fn operations(&self) -> usize {
+        2 * self.size
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<u64>() as f64 / self.samples.len() as f64
+    }
+
+    pub fn nanos_per_operation(&self) -> f64 {
+        self.mean() / self.operations() as f64
+    }
+
+    pub fn operations_per_second(&self) -> f64 {
+        1e9 / self.nanos_per_operation()
+    }
+}
+
+// the queue is created once per size and reused by every iteration
+pub fn measure<P, Q, F>(config: &Config, size: usize, new_queue: F) -> Vec<u64>
+    where P: Payload, Q: Queue<P>, F: FnOnce(usize) -> Q {
+    let mut queue = new_queue(size);
+    let mut samples = Vec::with_capacity(config.iterations);
+    for iteration in 0..config.warmup + config.iterations {
+        let start = Instant::now();
+        black_box(round_trip(&mut queue, size));
+        let elapsed = nanos(start.elapsed());
+        if iteration >= config.warmup {
+            samples.push(elapsed);
+        }
+    }
+    samples
+}
+
+fn round_trip<P: Payload, Q: Queue<P>>(queue: &mut Q, size: usize) -> P {
+    let mut item = P::zero();
+    for _ in 0..size {
+        queue.enqueue(item);
+        item = item.generate_next();
+    }
+    let mut consumed = P::zero();
+    while let Some(item) = queue.deque() {
+        consumed = item.accumulate(consumed);
+    }
+    consumed
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000_000_000 + duration.subsec_nanos() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    mod measurement {
+        use super::super::{measure, Config, Measurement};
+        use super::super::payload::{Payload8, PayloadSize};
+        use queues::sequential::array::ResizableArrayQueue;
+
+        #[test]
+        fn measure_takes_sample_per_iteration() {
+            let config = Config { warmup: 2, iterations: 3, ..Config::default() };
+
+            let samples = measure::<Payload8, _, _>(&config, 64, |size| ResizableArrayQueue::new(size));
+
+            assert_eq!(samples.len(), 3);
+        }
+
+        #[test]
+        fn operations_count_enqueues_and_deques() {
+            let measurement = Measurement {
+                queue: "queue",
+                size: 100,
+                payload: PayloadSize::new(8),
+                segment_size: None,
+                samples: vec![100, 300]
+            };
+
+            assert_eq!(measurement.operations(), 200);
+            assert_eq!(measurement.mean(), 200.0);
+            assert_eq!(measurement.nanos_per_operation(), 1.0);
+            assert_eq!(measurement.operations_per_second(), 1e9);
+        }
+    }
+}
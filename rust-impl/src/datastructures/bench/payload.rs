@@ -0,0 +1,202 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::Error;
+
+// what a benchmark moves through a queue, generated from the previous item and folded into a checksum
+// so that the optimizer cannot drop the work
+pub trait Payload: Copy {
+    fn zero() -> Self;
+
+    fn generate_next(self) -> Self;
+
+    fn accumulate(self, acc: Self) -> Self;
+}
+
+// identifies a payload type by its size and alignment, written `64` or `64@64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PayloadSize {
+    pub bytes: usize,
+    pub align: usize
+}
+
+impl PayloadSize {
+    pub fn new(bytes: usize) -> PayloadSize {
+        PayloadSize { bytes: bytes, align: 8 }
+    }
+
+    pub fn aligned(bytes: usize, align: usize) -> PayloadSize {
+        PayloadSize { bytes: bytes, align: align }
+    }
+}
+
+impl fmt::Display for PayloadSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.align == 8 {
+            write!(f, "{}", self.bytes)
+        } else {
+            write!(f, "{}@{}", self.bytes, self.align)
+        }
+    }
+}
+
+impl FromStr for PayloadSize {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<PayloadSize, Error> {
+        let mut parts = spec.splitn(2, '@');
+        let size = match (parts.next().map(str::parse), parts.next().map(str::parse)) {
+            (Some(Ok(bytes)), None) => PayloadSize::new(bytes),
+            (Some(Ok(bytes)), Some(Ok(align))) => PayloadSize::aligned(bytes, align),
+            _ => return Err(Error::InvalidArgument(format!("invalid payload `{}`, expected BYTES or BYTES@ALIGN", spec)))
+        };
+        if PAYLOAD_SIZES.contains(&size) { Ok(size) } else { Err(Error::UnsupportedPayload(size)) }
+    }
+}
+
+// lets code generic over the payload type be picked by a PayloadSize at run time
+pub trait PayloadFn {
+    type Output;
+
+    fn call<P: Payload>(self) -> Self::Output;
+}
+
+// every payload is a block of i64 words, cheap to generate and to fold whatever its size
+macro_rules! payloads {
+    ($($name:ident = $bytes:tt, align $align:tt;)*) => {
+        $(
+            #[repr(align($align))]
+            pub struct $name([i64; $bytes / 8]);
+
+            impl Payload for $name {
+                fn zero() -> Self {
+                    $name([0; $bytes / 8])
+                }
+
+                fn generate_next(mut self) -> Self {
+                    for word in self.0.iter_mut() {
+                        *word += 1;
+                    }
+                    self
+                }
+
+                fn accumulate(mut self, acc: Self) -> Self {
+                    for (word, acc) in self.0.iter_mut().zip(acc.0.iter()) {
+                        *word += *acc;
+                    }
+                    self
+                }
+            }
+
+            // arrays over 32 items have no derived impls
+            impl Clone for $name {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl Copy for $name {}
+
+            impl PartialEq for $name {
+                fn eq(&self, other: &Self) -> bool {
+                    self.0[..] == other.0[..]
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}({:?})", stringify!($name), &self.0[..])
+                }
+            }
+        )*
+
+        pub const PAYLOAD_SIZES: &'static [PayloadSize] = &[$(PayloadSize { bytes: $bytes, align: $align }),*];
+
+        pub fn with_payload<F: PayloadFn>(size: PayloadSize, f: F) -> Result<F::Output, Error> {
+            $(
+                if size == (PayloadSize { bytes: $bytes, align: $align }) {
+                    return Ok(f.call::<$name>());
+                }
+            )*
+            Err(Error::UnsupportedPayload(size))
+        }
+    }
+}
+
+payloads! {
+    Payload8 = 8, align 8;
+    Payload16 = 16, align 8;
+    Payload32 = 32, align 8;
+    Payload40 = 40, align 8;
+    Payload56 = 56, align 8;
+    Payload64 = 64, align 8;
+    Payload96 = 96, align 8;
+    Payload120 = 120, align 8;
+    Payload128 = 128, align 8;
+    Payload256 = 256, align 8;
+    Payload512 = 512, align 8;
+    Payload1024 = 1024, align 8;
+    Payload4096 = 4096, align 8;
+    CacheAlignedPayload64 = 64, align 64;
+    CacheAlignedPayload128 = 128, align 64;
+    CacheAlignedPayload256 = 256, align 64;
+    PageAlignedPayload4096 = 4096, align 4096;
+}
+
+#[cfg(test)]
+mod tests {
+    mod payload {
+        use std::mem;
+
+        use super::super::{with_payload, CacheAlignedPayload64, PageAlignedPayload4096, Payload, Payload120, Payload16,
+                           PayloadFn, PayloadSize, PAYLOAD_SIZES};
+        use super::super::super::Error;
+
+        struct Layout;
+
+        impl PayloadFn for Layout {
+            type Output = (usize, usize);
+
+            fn call<P: Payload>(self) -> (usize, usize) {
+                (mem::size_of::<P>(), mem::align_of::<P>())
+            }
+        }
+
+        #[test]
+        fn generate_and_accumulate() {
+            let item = Payload16::zero().generate_next().generate_next();
+
+            assert_eq!(item.accumulate(Payload16::zero().generate_next()), Payload16::zero().generate_next().generate_next().generate_next());
+        }
+
+        #[test]
+        fn payload_types_match_their_sizes() {
+            for &size in PAYLOAD_SIZES {
+                assert_eq!(with_payload(size, Layout), Ok((size.bytes, size.align)), "{}", size);
+            }
+            assert_eq!(mem::size_of::<Payload120>(), 120);
+            assert_eq!(mem::align_of::<CacheAlignedPayload64>(), 64);
+            assert_eq!(mem::align_of::<PageAlignedPayload4096>(), 4096);
+        }
+
+        #[test]
+        fn unsupported_payload() {
+            assert_eq!(with_payload(PayloadSize::new(12), Layout), Err(Error::UnsupportedPayload(PayloadSize::new(12))));
+        }
+
+        #[test]
+        fn parse_payload_sizes() {
+            assert_eq!("64".parse(), Ok(PayloadSize::new(64)));
+            assert_eq!("64@64".parse(), Ok(PayloadSize::aligned(64, 64)));
+            assert_eq!("4096@4096".parse(), Ok(PayloadSize::aligned(4096, 4096)));
+            assert_eq!("12".parse::<PayloadSize>(), Err(Error::UnsupportedPayload(PayloadSize::new(12))));
+            assert!("64@".parse::<PayloadSize>().is_err());
+        }
+
+        #[test]
+        fn display_payload_sizes() {
+            assert_eq!(PayloadSize::new(40).to_string(), "40");
+            assert_eq!(PayloadSize::aligned(128, 64).to_string(), "128@64");
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+
+use queues::Queue;
+use queues::sequential::{RcRefCellLinkLinkedArrayQueue, SharedLinkLinkedArrayQueue};
+use queues::sequential::array::{Doubling, HysteresisShrink, NeverShrink, NonResizableArrayQueue, ResizableArrayQueue};
+use queues::sequential::linked::{PooledSharedLinkedQueue, RcRefCellLinkedQueue, SharedLinkedQueue};
+
+use super::{measure, Config, Error, Measurement};
+use super::payload::{with_payload, Payload, PayloadFn, PayloadSize};
+
+// the queue expression sees the benchmarked size and the segment size under the given names
+macro_rules! entry {
+    ($name:expr, segmented: $segmented:expr, |$size:ident, $segment:pat| $queue:expr) => {{
+        struct Run<'a> {
+            config: &'a Config,
+            size: usize
+        }
+
+        impl <'a> PayloadFn for Run<'a> {
+            type Output = Vec<u64>;
+
+            fn call<P: Payload>(self) -> Vec<u64> {
+                let $segment = self.config.segment_size;
+                measure::<P, _, _>(self.config, self.size, |$size| $queue)
+            }
+        }
+
+        fn run(config: &Config, payload: PayloadSize, size: usize) -> Result<Vec<u64>, Error> {
+            with_payload(payload, Run { config: config, size: size })
+        }
+
+        Entry { name: $name, segmented: $segmented, run: run }
+    }}
+}
+
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub name: &'static str,
+    pub segmented: bool,
+    run: fn(&Config, PayloadSize, usize) -> Result<Vec<u64>, Error>
+}
+
+impl Entry {
+    pub fn run(&self, config: &Config) -> Result<Vec<Measurement>, Error> {
+        let mut measurements = Vec::with_capacity(config.payloads.len() * config.sizes.len());
+        for &payload in &config.payloads {
+            for &size in &config.sizes {
+                measurements.push(Measurement {
+                    queue: self.name,
+                    size: size,
+                    payload: payload,
+                    segment_size: if self.segmented { Some(config.segment_size) } else { None },
+                    samples: (self.run)(config, payload, size)?
+                });
+            }
+        }
+        Ok(measurements)
+    }
+}
+
+// every Queue implementation of the crate, plus VecDeque as the baseline to beat
+pub fn entries() -> Vec<Entry> {
+    vec![
+        entry!("std-vec-deque", segmented: false, |size, _| StdVecDeque(VecDeque::with_capacity(size))),
+        entry!("non-resizable-array-queue", segmented: false, |size, _| NonResizableArrayQueue::new(size)),
+        entry!("resizable-array-queue", segmented: false, |size, _| ResizableArrayQueue::new(size)),
+        entry!("resizable-array-queue-never-shrink", segmented: false,
+            |_size, _| ResizableArrayQueue::with_policy(16, NeverShrink(Doubling))),
+        entry!("resizable-array-queue-hysteresis-shrink", segmented: false,
+            |_size, _| ResizableArrayQueue::with_policy(16, HysteresisShrink::new(Doubling, 8, 16))),
+        entry!("rc-ref-cell-linked-queue", segmented: false, |_size, _| RcRefCellLinkedQueue::new()),
+        entry!("shared-linked-queue", segmented: false, |_size, _| SharedLinkedQueue::new()),
+        entry!("pooled-shared-linked-queue", segmented: false, |size, _| PooledSharedLinkedQueue::with_prewarm(size, size)),
+        entry!("rc-ref-cell-link-linked-array-queue", segmented: true,
+            |_size, segment| RcRefCellLinkLinkedArrayQueue::new(segment)),
+        entry!("shared-link-linked-array-queue", segmented: true,
+            |_size, segment| SharedLinkLinkedArrayQueue::new(segment))
+    ]
+}
+
+pub fn find(name: &str) -> Result<Entry, Error> {
+    entries().into_iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| Error::UnknownQueue(name.to_owned()))
+}
+
+struct StdVecDeque<E>(VecDeque<E>);
+
+impl <E> Queue<E> for StdVecDeque<E> {
+    fn enqueue(&mut self, item: E) {
+        self.0.push_back(item)
+    }
+
+    fn deque(&mut self) -> Option<E> {
+        self.0.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn peek(&self) -> Option<&E> {
+        self.0.front()
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut E> {
+        self.0.front_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod registry {
+        use super::super::{entries, find};
+        use super::super::super::{Config, Error};
+        use super::super::super::payload::PayloadSize;
+
+        fn tiny(payloads: Vec<PayloadSize>) -> Config {
+            Config {
+                sizes: vec![16, 64],
+                payloads: payloads,
+                segment_size: 8,
+                warmup: 1,
+                iterations: 2
+            }
+        }
+
+        #[test]
+        fn names_are_unique() {
+            let mut names = entries().iter().map(|entry| entry.name).collect::<Vec<_>>();
+            names.sort();
+            names.dedup();
+
+            assert_eq!(names.len(), entries().len());
+        }
+
+        #[test]
+        fn every_entry_measures_every_size() {
+            for entry in entries() {
+                let measurements = entry.run(&tiny(vec![PayloadSize::new(16)])).unwrap();
+
+                assert_eq!(measurements.iter().map(|m| m.size).collect::<Vec<_>>(), vec![16, 64]);
+                assert!(measurements.iter().all(|m| m.samples.len() == 2 && m.payload == PayloadSize::new(16)));
+            }
+        }
+
+        #[test]
+        fn sweep_over_payloads() {
+            let payloads = vec![PayloadSize::new(8), PayloadSize::aligned(64, 64), PayloadSize::aligned(4096, 4096)];
+
+            let measurements = find("shared-linked-queue").unwrap().run(&tiny(payloads.clone())).unwrap();
+
+            assert_eq!(measurements.iter().map(|m| (m.payload, m.size)).collect::<Vec<_>>(), vec![
+                (payloads[0], 16), (payloads[0], 64),
+                (payloads[1], 16), (payloads[1], 64),
+                (payloads[2], 16), (payloads[2], 64)
+            ]);
+        }
+
+        #[test]
+        fn only_segmented_entries_record_segment_size() {
+            let segmented = find("shared-link-linked-array-queue").unwrap().run(&tiny(vec![PayloadSize::new(8)])).unwrap();
+            let linked = find("shared-linked-queue").unwrap().run(&tiny(vec![PayloadSize::new(8)])).unwrap();
+
+            assert_eq!(segmented[0].segment_size, Some(8));
+            assert_eq!(linked[0].segment_size, None);
+        }
+
+        #[test]
+        fn unknown_queue() {
+            assert_eq!(find("no-such-queue").err(), Some(Error::UnknownQueue("no-such-queue".to_owned())));
+        }
+
+        #[test]
+        fn unsupported_payload() {
+            let entry = find("resizable-array-queue").unwrap();
+
+            assert_eq!(entry.run(&tiny(vec![PayloadSize::new(12)])).err(), Some(Error::UnsupportedPayload(PayloadSize::new(12))));
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::Error;
+use super::jmh::{self, TimeUnit};
+
+// a JMH result of a benchmark parametrized by the queue size, the throughput counts
+// enqueues and deques per second the same way Measurement::operations_per_second does
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub benchmark: String,
+    pub mode: String,
+    pub params: Vec<(String, String)>,
+    pub size: usize,
+    pub throughput: f64,
+    pub error: f64
+}
+
+// equivalent implementations, java benchmarks are named by their JMH method and rust ones by their registry entry
+pub struct Comparison {
+    pub title: &'static str,
+    pub java: &'static [&'static str],
+    pub rust: &'static [&'static str]
+}
+
+pub const COMPARISONS: &'static [Comparison] = &[
+    Comparison {
+        title: "Linked versus array queues",
+        java: &["ua.ds.experiments.n01.LinkedVsArray.linked_g1", "ua.ds.experiments.n01.LinkedVsArray.array"],
+        rust: &["shared-linked-queue", "resizable-array-queue"]
+    },
+    Comparison {
+        title: "Linked queues",
+        java: &["ua.ds.experiments.n01.LinkedVsArray.linked_g1", "ua.ds.experiments.n01.LinkedVsArray.linked_parallel",
+                "ua.ds.experiments.n01.LinkedVsArray.linked_cms"],
+        rust: &["shared-linked-queue", "rc-ref-cell-linked-queue"]
+    },
+    Comparison {
+        title: "Preallocated array queues",
+        java: &["ua.ds.experiments.n01.LinkedVsArray.array", "ua.ds.experiments.n01.LinkedVsArray.array_inline"],
+        rust: &["non-resizable-array-queue", "resizable-array-queue"]
+    },
+    Comparison {
+        title: "Shrinking versus not shrinking array queues",
+        java: &["ua.ds.experiments.n03.ShrinkVsNotShrink.shrink_mask", "ua.ds.experiments.n03.ShrinkVsNotShrink.not_shrink_mask"],
+        rust: &["resizable-array-queue", "resizable-array-queue-never-shrink", "resizable-array-queue-hysteresis-shrink"]
+    },
+    Comparison {
+        title: "Pooled linked queues",
+        java: &["ua.ds.experiments.n04.LinkedPooledQueues.pooled_g1"],
+        rust: &["pooled-shared-linked-queue", "shared-linked-queue"]
+    }
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Java,
+    Rust
+}
+
+// scores of one benchmark with one set of parameters besides the size, ordered by size
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub label: String,
+    pub language: Language,
+    pub scores: Vec<Score>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: &'static str,
+    pub series: Vec<Series>
+}
+
+impl Section {
+    pub fn slug(&self) -> String {
+        let slug = self.title.to_lowercase().chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+        slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+    }
+
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = self.series.iter()
+            .flat_map(|series| series.scores.iter().map(|score| score.size))
+            .collect::<Vec<_>>();
+        sizes.sort();
+        sizes.dedup();
+        sizes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Html
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            Format::Markdown => "md",
+            Format::Html => "html"
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(label: &str) -> Result<Format, Error> {
+        match label {
+            "md" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            _ => Err(Error::InvalidArgument(format!("unknown report format `{}`, expected md or html", label)))
+        }
+    }
+}
+
+// results without a size parameter measure a single operation and have nothing to line up with
+pub fn scores(json: &Value) -> Result<Vec<Score>, Error> {
+    let results = json.as_array().ok_or_else(|| invalid("expected an array of JMH results"))?;
+    let mut scores = Vec::with_capacity(results.len());
+    for result in results {
+        let benchmark = field(result, "benchmark")?.as_str().ok_or_else(|| invalid("`benchmark` is not a string"))?;
+        let mode = field(result, "mode")?.as_str().ok_or_else(|| invalid("`mode` is not a string"))?;
+        let mut params = vec![];
+        let mut size = None;
+        if let Some(json) = result.get("params") {
+            let fields = json.as_object().ok_or_else(|| invalid(&format!("params of `{}` are not an object", benchmark)))?;
+            for (name, value) in fields {
+                let value = value.as_str().ok_or_else(|| invalid(&format!("param `{}` of `{}` is not a string", name, benchmark)))?;
+                if name == "size" {
+                    size = Some(value.parse().map_err(|_| invalid(&format!("size `{}` of `{}` is not a number", value, benchmark)))?);
+                } else {
+                    params.push((name.clone(), value.to_owned()));
+                }
+            }
+        }
+        let size = match size {
+            Some(size) => size,
+            None => continue
+        };
+        let metric = field(result, "primaryMetric")?;
+        let score = number(metric, "score")?;
+        let error = number(metric, "scoreError")?;
+        let unit = field(metric, "scoreUnit")?.as_str().ok_or_else(|| invalid("`scoreUnit` is not a string"))?;
+        if !(score.is_finite() && score > 0.0) {
+            return Err(invalid(&format!("score `{}` of `{}` at size {} is not a positive number", score, benchmark, size)));
+        }
+        let throughput = invocations_per_second(score, unit)? * 2.0 * size as f64;
+        scores.push(Score {
+            benchmark: benchmark.to_owned(),
+            mode: mode.to_owned(),
+            params: params,
+            size: size,
+            throughput: throughput,
+            error: throughput * error / score
+        });
+    }
+    Ok(scores)
+}
+
+// an invocation of every compared benchmark is a round trip of `size` enqueues and deques
+fn invocations_per_second(score: f64, unit: &str) -> Result<f64, Error> {
+    let mut parts = unit.splitn(2, '/');
+    let (per_op, time) = match (parts.next(), parts.next()) {
+        (Some("ops"), Some(time)) => (false, time),
+        (Some(time), Some("op")) => (true, time),
+        _ => return Err(invalid(&format!("unsupported score unit `{}`", unit)))
+    };
+    let nanos = time.parse::<TimeUnit>().map_err(|_| invalid(&format!("unsupported score unit `{}`", unit)))?.nanos();
+    Ok(if per_op { 1e9 / (score * nanos) } else { score * 1e9 / nanos })
+}
+
+fn field<'a>(json: &'a Value, key: &str) -> Result<&'a Value, Error> {
+    json.get(key).ok_or_else(|| invalid(&format!("missing `{}`", key)))
+}
+
+fn number(json: &Value, key: &str) -> Result<f64, Error> {
+    jmh::as_number(field(json, key)?).ok_or_else(|| invalid(&format!("`{}` is not a number", key)))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidResults(format!("invalid JMH results: {}", message))
+}
+
+// a comparison needs results from both languages, the others are left out
+pub fn sections(java: &[Score], rust: &[Score]) -> Vec<Section> {
+    COMPARISONS.iter()
+        .map(|comparison| {
+            let mut series = vec![];
+            for &benchmark in comparison.java {
+                series.extend(collect(java, benchmark, &format!("java {}", java_name(benchmark)), Language::Java));
+            }
+            for &queue in comparison.rust {
+                series.extend(collect(rust, &jmh::benchmark_name(queue), &format!("rust {}", queue), Language::Rust));
+            }
+            Section { title: comparison.title, series: series }
+        })
+        .filter(|section| section.series.iter().any(|series| series.language == Language::Java)
+            && section.series.iter().any(|series| series.language == Language::Rust))
+        .collect()
+}
+
+// drops the package, leaving the class and the method
+fn java_name(benchmark: &str) -> &str {
+    match benchmark.rmatch_indices('.').nth(1) {
+        Some((index, _)) => &benchmark[index + 1..],
+        None => benchmark
+    }
+}
+
+// JMH runs every benchmark in each requested mode, the modes measure differently and get a series each
+fn collect(scores: &[Score], benchmark: &str, name: &str, language: Language) -> Vec<Series> {
+    let mut series: Vec<Series> = vec![];
+    for score in scores.iter().filter(|score| score.benchmark == benchmark) {
+        let label = if score.params.is_empty() {
+            format!("{} {}", name, score.mode)
+        } else {
+            let params = score.params.iter().map(|&(ref name, ref value)| format!("{}={}", name, value)).collect::<Vec<_>>();
+            format!("{} {} ({})", name, score.mode, params.join(", "))
+        };
+        match series.iter().position(|series| series.label == label) {
+            Some(index) => series[index].scores.push(score.clone()),
+            None => series.push(Series { label: label, language: language, scores: vec![score.clone()] })
+        }
+    }
+    for series in &mut series {
+        series.scores.sort_by_key(|score| score.size);
+    }
+    series
+}
+
+pub fn render(sections: &[Section], format: Format) -> String {
+    match format {
+        Format::Markdown => markdown(sections),
+        Format::Html => html(sections)
+    }
+}
+
+const TITLE: &'static str = "Queue throughput, Java versus Rust";
+
+const LEGEND: &'static str = "Throughput is millions of enqueues and deques per second, \
+    with the 99.9% confidence interval JMH reports. Java series are dashed on the charts.";
+
+pub fn markdown(sections: &[Section]) -> String {
+    let mut out = format!("# {}\n\n{}\n", TITLE, LEGEND);
+    for section in sections {
+        out.push_str(&format!("\n## {}\n\n![{}]({}.svg)\n\n", section.title, section.title, section.slug()));
+        out.push_str("| size |");
+        for series in &section.series {
+            out.push_str(&format!(" {} |", series.label));
+        }
+        out.push_str("\n|---:|");
+        for _ in &section.series {
+            out.push_str("---:|");
+        }
+        out.push('\n');
+        for size in section.sizes() {
+            out.push_str(&format!("| {} |", size));
+            for series in &section.series {
+                out.push_str(&format!(" {} |", cell(series, size)));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+pub fn html(sections: &[Section]) -> String {
+    let mut out = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+        <style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+        th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: right; }}</style>\n\
+        </head>\n<body>\n<h1>{}</h1>\n<p>{}</p>\n", TITLE, TITLE, LEGEND);
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n<img src=\"{}.svg\" alt=\"{}\">\n<table>\n<tr><th>size</th>",
+            escape(section.title), section.slug(), escape(section.title)));
+        for series in &section.series {
+            out.push_str(&format!("<th>{}</th>", escape(&series.label)));
+        }
+        out.push_str("</tr>\n");
+        for size in section.sizes() {
+            out.push_str(&format!("<tr><td>{}</td>", size));
+            for series in &section.series {
+                out.push_str(&format!("<td>{}</td>", escape(&cell(series, size))));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn cell(series: &Series, size: usize) -> String {
+    match series.scores.iter().find(|score| score.size == size) {
+        Some(score) if score.error.is_finite() => format!("{:.2} ± {:.2}", score.throughput / 1e6, score.error / 1e6),
+        Some(score) => format!("{:.2}", score.throughput / 1e6),
+        None => "-".to_owned()
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    mod report {
+        use serde_json::Value;
+
+        use super::super::{markdown, scores, sections, Language, Score, Section};
+        use super::super::super::Error;
+        use super::super::super::jmh::number;
+
+        fn result(benchmark: &str, params: Vec<(&str, &str)>, score: f64, unit: &str) -> Value {
+            json!({
+                "benchmark": benchmark,
+                "mode": if unit.starts_with("ops/") { "thrpt" } else { "avgt" },
+                "params": Value::Object(params.into_iter().map(|(name, value)| (name.to_owned(), json!(value))).collect()),
+                "primaryMetric": {
+                    "score": number(score),
+                    "scoreError": "NaN",
+                    "scoreUnit": unit
+                }
+            })
+        }
+
+        fn score(benchmark: &str, params: Vec<(&str, &str)>, size: usize, throughput: f64) -> Score {
+            Score {
+                benchmark: benchmark.to_owned(),
+                mode: "avgt".to_owned(),
+                params: params.into_iter().map(|(name, value)| (name.to_owned(), value.to_owned())).collect(),
+                size: size,
+                throughput: throughput,
+                error: ::std::f64::NAN
+            }
+        }
+
+        #[test]
+        fn convert_scores_to_throughput() {
+            let json = json!([
+                result("ua.ds.experiments.n01.LinkedVsArray.array", vec![("size", "1024")], 2.048, "us/op"),
+                result("datastructures.queue_bench.std_vec_deque", vec![("size", "1024"), ("payload", "8")], 500.0, "ops/ms"),
+                result("ua.ds.LinkedVsArrayPerOp.Small.array", vec![], 3.0, "ns/op")
+            ]);
+
+            let scores = scores(&json).unwrap();
+
+            assert_eq!(scores.len(), 2);
+            assert_eq!(scores[0].throughput, 1e9);
+            assert_eq!(scores[1].throughput, 1024e6);
+            assert_eq!(scores[1].params, vec![("payload".to_owned(), "8".to_owned())]);
+            assert!(scores[0].error.is_nan());
+        }
+
+        #[test]
+        fn reject_scores_without_a_throughput() {
+            for &score in &[0.0, -1.0, ::std::f64::NAN] {
+                let json = json!([result("queue", vec![("size", "1024")], score, "us/op")]);
+
+                assert!(scores(&json).is_err(), "{}", score);
+            }
+        }
+
+        #[test]
+        fn reject_unknown_units() {
+            let json = json!([result("queue", vec![("size", "1024")], 1.0, "ops/min")]);
+
+            assert_eq!(scores(&json), Err(Error::InvalidResults("invalid JMH results: unsupported score unit `ops/min`".to_owned())));
+        }
+
+        #[test]
+        fn pair_equivalent_implementations() {
+            let java = vec![
+                score("ua.ds.experiments.n01.LinkedVsArray.linked_g1", vec![], 2048, 2.0),
+                score("ua.ds.experiments.n01.LinkedVsArray.linked_g1", vec![], 1024, 1.0),
+                score("ua.ds.experiments.n04.LinkedPooledQueues.pooled_g1", vec![("poolType", "p")], 1024, 1.0)
+            ];
+            let rust = vec![
+                score("datastructures.queue_bench.shared_linked_queue", vec![("payload", "8")], 1024, 3.0),
+                score("datastructures.queue_bench.shared_linked_queue", vec![("payload", "64")], 1024, 4.0)
+            ];
+
+            let sections = sections(&java, &rust);
+
+            assert_eq!(sections.iter().map(|section| section.title).collect::<Vec<_>>(),
+                vec!["Linked versus array queues", "Linked queues", "Pooled linked queues"]);
+            let labels = sections[0].series.iter().map(|series| (series.label.as_str(), series.language)).collect::<Vec<_>>();
+            assert_eq!(labels, vec![
+                ("java LinkedVsArray.linked_g1 avgt", Language::Java),
+                ("rust shared-linked-queue avgt (payload=8)", Language::Rust),
+                ("rust shared-linked-queue avgt (payload=64)", Language::Rust)
+            ]);
+            assert_eq!(sections[0].series[0].scores.iter().map(|score| score.size).collect::<Vec<_>>(), vec![1024, 2048]);
+            assert_eq!(sections[2].series[0].label, "java LinkedPooledQueues.pooled_g1 avgt (poolType=p)");
+        }
+
+        #[test]
+        fn series_per_mode() {
+            let mut single_shot = result("ua.ds.experiments.n01.LinkedVsArray.linked_g1", vec![("size", "1024")], 4.096, "us/op");
+            single_shot["mode"] = json!("ss");
+            let java = scores(&json!([
+                single_shot,
+                result("ua.ds.experiments.n01.LinkedVsArray.linked_g1", vec![("size", "1024")], 2.048, "us/op"),
+                result("ua.ds.experiments.n01.LinkedVsArray.linked_g1", vec![("size", "2048")], 2.048, "us/op")
+            ])).unwrap();
+            let rust = vec![score("datastructures.queue_bench.shared_linked_queue", vec![], 1024, 1.0)];
+
+            let sections = sections(&java, &rust);
+
+            let series = sections[0].series.iter()
+                .map(|series| (series.label.as_str(), series.scores.iter().map(|score| (score.size, score.throughput)).collect::<Vec<_>>()))
+                .collect::<Vec<_>>();
+            assert_eq!(series, vec![
+                ("java LinkedVsArray.linked_g1 ss", vec![(1024, 5e8)]),
+                ("java LinkedVsArray.linked_g1 avgt", vec![(1024, 1e9), (2048, 2e9)]),
+                ("rust shared-linked-queue avgt", vec![(1024, 1.0)])
+            ]);
+        }
+
+        #[test]
+        fn skip_comparisons_without_both_languages() {
+            let java = vec![score("ua.ds.experiments.n01.LinkedVsArray.array", vec![], 1024, 1.0)];
+
+            assert_eq!(sections(&java, &[]), vec![]);
+        }
+
+        #[test]
+        fn markdown_table_per_section() {
+            let java = vec![score("ua.ds.experiments.n01.LinkedVsArray.linked_g1", vec![], 1024, 1.5e6)];
+            let rust = vec![score("datastructures.queue_bench.shared_linked_queue", vec![], 2048, 2.5e6)];
+            let sections = sections(&java, &rust);
+
+            let markdown = markdown(&sections[..1]);
+
+            assert!(markdown.contains("## Linked versus array queues\n\n![Linked versus array queues](linked-versus-array-queues.svg)\n\n\
+                | size | java LinkedVsArray.linked_g1 avgt | rust shared-linked-queue avgt |\n|---:|---:|---:|\n\
+                | 1024 | 1.50 | - |\n| 2048 | - | 2.50 |\n"));
+        }
+
+        #[test]
+        fn slug_of_title() {
+            let section = Section { title: "Shrinking versus not shrinking array queues", series: vec![] };
+
+            assert_eq!(section.slug(), "shrinking-versus-not-shrinking-array-queues");
+        }
+    }
+}
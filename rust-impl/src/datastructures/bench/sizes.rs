@@ -0,0 +1,300 @@
+use std::str::FromStr;
+
+use super::Error;
+
+pub const L1_BYTES: usize = 32 * 1024;
+pub const L2_BYTES: usize = 256 * 1024;
+pub const L3_BYTES: usize = 8 * 1024 * 1024;
+
+// fractions of a cache capacity, in items, that a cache sweep visits
+const CACHE_SWEEP: [(usize, usize); 7] = [(1, 2), (3, 4), (7, 8), (1, 1), (9, 8), (5, 4), (3, 2)];
+
+// every strategy yields sizes in items, exponent ranges exclude their upper bound
+#[derive(Debug, Clone, PartialEq)]
+pub enum Strategy {
+    // 2^min, 2^(min + 1), ..., 2^(max - 1)
+    Powers { min: u32, max: u32 },
+    // from 2^n to 2^(n + 1) by step for every n in min..max
+    Steps { min: u32, max: u32, step: usize },
+    // from start multiplied by ratio while it stays within end
+    Geometric { start: usize, end: usize, ratio: f64 },
+    // around the capacity of every cache in items of the given size
+    Caches { item_bytes: usize, caches: Vec<usize> },
+    // count uniform samples from min..max, the same seed gives the same sizes
+    Random { min: usize, max: usize, count: usize, seed: u64 },
+    Composite(Vec<Strategy>)
+}
+
+impl Strategy {
+    pub fn powers(min: u32, max: u32) -> Strategy {
+        Strategy::Powers { min: min, max: max }
+    }
+
+    pub fn steps(min: u32, max: u32, step: usize) -> Strategy {
+        Strategy::Steps { min: min, max: max, step: step }
+    }
+
+    pub fn geometric(start: usize, end: usize, ratio: f64) -> Strategy {
+        Strategy::Geometric { start: start, end: end, ratio: ratio }
+    }
+
+    pub fn caches(item_bytes: usize) -> Strategy {
+        Strategy::Caches { item_bytes: item_bytes, caches: vec![L1_BYTES, L2_BYTES, L3_BYTES] }
+    }
+
+    pub fn random(min: usize, max: usize, count: usize, seed: u64) -> Strategy {
+        Strategy::Random { min: min, max: max, count: count, seed: seed }
+    }
+
+    pub fn and(self, other: Strategy) -> Strategy {
+        match self {
+            Strategy::Composite(mut strategies) => {
+                strategies.push(other);
+                Strategy::Composite(strategies)
+            }
+            strategy => Strategy::Composite(vec![strategy, other])
+        }
+    }
+
+    // sorted and without duplicates, so composed strategies never measure a size twice,
+    // and without zero, which leaves nothing to measure
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![];
+        self.collect(&mut sizes);
+        sizes.retain(|&size| size > 0);
+        sizes.sort();
+        sizes.dedup();
+        sizes
+    }
+
+    fn collect(&self, sizes: &mut Vec<usize>) {
+        match *self {
+            Strategy::Powers { min, max } => sizes.extend((min..max).map(|power| 2usize.pow(power))),
+            Strategy::Steps { min, max, step } => {
+                for power in min..max {
+                    let mut size = 2usize.pow(power);
+                    while size <= 2usize.pow(power + 1) {
+                        sizes.push(size);
+                        size += step;
+                    }
+                }
+            }
+            Strategy::Geometric { start, end, ratio } => {
+                let mut size = start;
+                while size <= end {
+                    sizes.push(size);
+                    size = ::std::cmp::max(size + 1, (size as f64 * ratio).round() as usize);
+                }
+            }
+            Strategy::Caches { item_bytes, ref caches } => {
+                for &cache in caches {
+                    let items = cache / item_bytes;
+                    sizes.extend(CACHE_SWEEP.iter().map(|&(numerator, denominator)| items * numerator / denominator));
+                }
+            }
+            Strategy::Random { min, max, count, seed } => {
+                let mut random = SplitMix64(seed);
+                sizes.extend((0..count).map(|_| min + (random.next() % (max - min) as u64) as usize));
+            }
+            Strategy::Composite(ref strategies) => {
+                for strategy in strategies {
+                    strategy.collect(sizes);
+                }
+            }
+        }
+    }
+}
+
+// specs are `pow2:10..26`, `step:13..20/1024`, `geo:1024..65536*1.5`, `cache:64`,
+// `cache:64@32k,256k,8m` or `rand:1024..65536x16@42`, joined with `+` to compose them
+impl FromStr for Strategy {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Strategy, Error> {
+        if spec.contains('+') {
+            let strategies = spec.split('+').map(|part| part.parse()).collect::<Result<Vec<_>, _>>()
+                .map_err(|_| Error::InvalidSizeSpec(spec.to_owned()))?;
+            return Ok(Strategy::Composite(strategies));
+        }
+        let invalid = || Error::InvalidSizeSpec(spec.to_owned());
+        let mut parts = spec.splitn(2, ':');
+        let (kind, args) = match (parts.next(), parts.next()) {
+            (Some(kind), Some(args)) => (kind, args),
+            _ => return Err(invalid())
+        };
+        let strategy = match kind {
+            "pow2" => {
+                let (min, max) = range(args).ok_or_else(&invalid)?;
+                Strategy::powers(min, max)
+            }
+            "step" => {
+                let (range_args, step) = split_once(args, '/').ok_or_else(&invalid)?;
+                let (min, max) = range(range_args).ok_or_else(&invalid)?;
+                Strategy::steps(min, max, step.parse().map_err(|_| invalid())?)
+            }
+            "geo" => {
+                let (range_args, ratio) = split_once(args, '*').ok_or_else(&invalid)?;
+                let (start, end) = range(range_args).ok_or_else(&invalid)?;
+                Strategy::geometric(start, end, ratio.parse().map_err(|_| invalid())?)
+            }
+            "cache" => match split_once(args, '@') {
+                Some((item_bytes, caches)) => Strategy::Caches {
+                    item_bytes: bytes(item_bytes).ok_or_else(&invalid)?,
+                    caches: caches.split(',').map(bytes).collect::<Option<Vec<_>>>().ok_or_else(&invalid)?
+                },
+                None => Strategy::caches(bytes(args).ok_or_else(&invalid)?)
+            },
+            "rand" => {
+                let (sampled, seed) = split_once(args, '@').ok_or_else(&invalid)?;
+                let (range_args, count) = split_once(sampled, 'x').ok_or_else(&invalid)?;
+                let (min, max) = range(range_args).ok_or_else(&invalid)?;
+                Strategy::random(min, max, count.parse().map_err(|_| invalid())?, seed.parse().map_err(|_| invalid())?)
+            }
+            _ => return Err(invalid())
+        };
+        if strategy.is_valid() { Ok(strategy) } else { Err(invalid()) }
+    }
+}
+
+impl Strategy {
+    fn is_valid(&self) -> bool {
+        match *self {
+            Strategy::Powers { min, max } => min < max && max < 64,
+            Strategy::Steps { min, max, step } => min < max && max < 63 && step > 0,
+            Strategy::Geometric { start, end, ratio } => start > 0 && start <= end && ratio > 1.0,
+            // the smallest fraction of the sweep is a half, so every cache holds at least two items
+            Strategy::Caches { item_bytes, ref caches } =>
+                item_bytes > 0 && !caches.is_empty() && caches.iter().all(|&cache| cache >= 2 * item_bytes),
+            Strategy::Random { min, max, count, .. } => min < max && count > 0,
+            Strategy::Composite(ref strategies) => strategies.iter().all(Strategy::is_valid)
+        }
+    }
+}
+
+fn split_once(args: &str, separator: char) -> Option<(&str, &str)> {
+    args.find(separator).map(|index| (&args[..index], &args[index + separator.len_utf8()..]))
+}
+
+fn range<T: FromStr>(args: &str) -> Option<(T, T)> {
+    let mut bounds = args.splitn(2, "..");
+    match (bounds.next().map(str::parse), bounds.next().map(str::parse)) {
+        (Some(Ok(min)), Some(Ok(max))) => Some((min, max)),
+        _ => None
+    }
+}
+
+// accepts plain bytes or a k, m or g suffix
+fn bytes(arg: &str) -> Option<usize> {
+    let (digits, unit) = match arg.chars().last() {
+        Some('k') => (&arg[..arg.len() - 1], 1024),
+        Some('m') => (&arg[..arg.len() - 1], 1024 * 1024),
+        Some('g') => (&arg[..arg.len() - 1], 1024 * 1024 * 1024),
+        _ => (arg, 1)
+    };
+    digits.parse::<usize>().ok().map(|digits| digits * unit)
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod strategy {
+        use super::super::{Strategy, L1_BYTES};
+        use super::super::super::Error;
+
+        #[test]
+        fn powers_of_two() {
+            assert_eq!(Strategy::powers(10, 13).sizes(), vec![1024, 2048, 4096]);
+        }
+
+        #[test]
+        fn steps_between_powers_of_two() {
+            let sizes = Strategy::steps(17, 18, 1024).sizes();
+
+            assert_eq!(sizes.len(), 129);
+            assert_eq!(sizes[0], 131072);
+            assert_eq!(sizes[100], 131072 + 100 * 1024);
+            assert_eq!(sizes[128], 262144);
+        }
+
+        #[test]
+        fn steps_do_not_repeat_shared_bounds() {
+            let sizes = Strategy::steps(10, 12, 512).sizes();
+
+            assert_eq!(sizes, vec![1024, 1536, 2048, 2560, 3072, 3584, 4096]);
+        }
+
+        #[test]
+        fn geometric_with_custom_ratio() {
+            assert_eq!(Strategy::geometric(100, 500, 1.5).sizes(), vec![100, 150, 225, 338]);
+            assert_eq!(Strategy::geometric(1, 4, 1.1).sizes(), vec![1, 2, 3, 4]);
+        }
+
+        #[test]
+        fn sweep_around_caches() {
+            let sizes = Strategy::caches(64).sizes();
+            let l1 = L1_BYTES / 64;
+
+            assert_eq!(sizes.len(), 21);
+            assert_eq!(&sizes[..7], &[l1 / 2, l1 * 3 / 4, l1 * 7 / 8, l1, l1 * 9 / 8, l1 * 5 / 4, l1 * 3 / 2]);
+        }
+
+        #[test]
+        fn random_samples_are_reproducible() {
+            let first = Strategy::random(1000, 2000, 16, 42).sizes();
+            let second = Strategy::random(1000, 2000, 16, 42).sizes();
+            let other = Strategy::random(1000, 2000, 16, 43).sizes();
+
+            assert_eq!(first, second);
+            assert!(first != other);
+            assert!(first.iter().all(|&size| size >= 1000 && size < 2000));
+        }
+
+        #[test]
+        fn zero_sizes_are_dropped() {
+            let strategy = "rand:0..2x64@42".parse::<Strategy>().unwrap();
+
+            assert_eq!(strategy.sizes(), vec![1]);
+        }
+
+        #[test]
+        fn composed_strategies() {
+            let strategy = Strategy::powers(10, 12).and(Strategy::steps(10, 11, 512)).and(Strategy::powers(20, 21));
+
+            assert_eq!(strategy.sizes(), vec![1024, 1536, 2048, 1048576]);
+        }
+
+        #[test]
+        fn parse_specs() {
+            assert_eq!("pow2:10..26".parse(), Ok(Strategy::powers(10, 26)));
+            assert_eq!("step:13..20/1024".parse(), Ok(Strategy::steps(13, 20, 1024)));
+            assert_eq!("geo:1024..65536*1.5".parse(), Ok(Strategy::geometric(1024, 65536, 1.5)));
+            assert_eq!("cache:64".parse(), Ok(Strategy::caches(64)));
+            assert_eq!("cache:16@48k,2m".parse(), Ok(Strategy::Caches { item_bytes: 16, caches: vec![48 * 1024, 2 * 1024 * 1024] }));
+            assert_eq!("rand:1024..65536x16@42".parse(), Ok(Strategy::random(1024, 65536, 16, 42)));
+        }
+
+        #[test]
+        fn parse_composed_spec() {
+            assert_eq!("pow2:10..12+step:13..14/1024".parse(), Ok(Strategy::Composite(vec![Strategy::powers(10, 12), Strategy::steps(13, 14, 1024)])));
+        }
+
+        #[test]
+        fn reject_invalid_specs() {
+            for spec in &["", "pow2", "pow2:10", "pow2:12..10", "step:13..20", "step:13..20/0",
+                          "geo:1..10*0.5", "cache:", "cache:64@32", "cache:64@1k,64", "rand:10..20x5", "fib:1..10", "pow2:1..2+"] {
+                assert_eq!(spec.parse::<Strategy>(), Err(Error::InvalidSizeSpec(spec.to_string())), "{}", spec);
+            }
+        }
+    }
+}
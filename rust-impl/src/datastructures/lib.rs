@@ -3,5 +3,11 @@
 #![feature(const_fn)]
 #![feature(test)]
 #![feature(shared)]
+#![feature(repr_align)]
+#![feature(attr_literals)]
 
+#[macro_use]
+extern crate serde_json;
+
+pub mod bench;
 pub mod queues;
@@ -0,0 +1,862 @@
+use std::ptr;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use std::usize;
+
+use super::ConcurrentQueue;
+use super::padding::{CacheLine, CachePadded, NoPadding, Padding};
+use super::super::{BoundedQueue, Full, Queue};
+use super::super::sequential::array::{NonResizableArrayQueue, OverflowPolicy};
+
+struct Node<T> {
+    item: Option<T>,
+    next: *mut Node<T>
+}
+
+impl <T> Node<T> {
+    fn new(item: Option<T>) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            item: item,
+            next: ptr::null_mut()
+        }))
+    }
+}
+
+unsafe fn free_nodes<T>(mut node: *mut Node<T>) {
+    while !node.is_null() {
+        let next = (*node).next;
+        drop(Box::from_raw(node));
+        node = next;
+    }
+}
+
+// waits on the condition while the queue is blocked, gives the guard back only if
+// the queue got unblocked before the deadline, no deadline means wait forever
+fn wait_while<'a, S, F>(condition: &Condvar, mut guard: MutexGuard<'a, S>, deadline: Option<Instant>, blocked: F)
+    -> Option<MutexGuard<'a, S>>
+    where F: Fn(&S) -> bool {
+    while blocked(&guard) {
+        match deadline {
+            None => guard = condition.wait(guard).unwrap(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                guard = condition.wait_timeout(guard, deadline - now).unwrap().0;
+            }
+        }
+    }
+    Some(guard)
+}
+
+struct Links<T, P: Padding> {
+    head: CachePadded<*mut Node<T>, P>,
+    tail: CachePadded<*mut Node<T>, P>,
+    size: usize
+}
+
+unsafe impl <T: Send, P: Padding> Send for Links<T, P> {}
+
+impl <T, P: Padding> Links<T, P> {
+    fn push(&mut self, item: T) {
+        let node = Node::new(Some(item));
+        unsafe { (**self.tail).next = node; }
+        *self.tail = node;
+        self.size += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        unsafe {
+            let first = *self.head;
+            let next = (*first).next;
+            *self.head = next;
+            self.size -= 1;
+            drop(Box::from_raw(first));
+            (*next).item.take()
+        }
+    }
+}
+
+impl <T, P: Padding> Drop for Links<T, P> {
+    fn drop(&mut self) {
+        unsafe { free_nodes(*self.head); }
+    }
+}
+
+pub struct SingleLockLinkedBlockingQueue<T, P: Padding = NoPadding> {
+    links: Mutex<Links<T, P>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize
+}
+
+pub type PaddedSingleLockLinkedBlockingQueue<T> = SingleLockLinkedBlockingQueue<T, CacheLine>;
+
+impl <T> SingleLockLinkedBlockingQueue<T> {
+    pub fn new() -> SingleLockLinkedBlockingQueue<T> {
+        SingleLockLinkedBlockingQueue::with_padding(None, NoPadding)
+    }
+
+    pub fn with_capacity(capacity: usize) -> SingleLockLinkedBlockingQueue<T> {
+        SingleLockLinkedBlockingQueue::with_padding(Some(capacity), NoPadding)
+    }
+}
+
+impl <T, P: Padding> SingleLockLinkedBlockingQueue<T, P> {
+    pub fn with_padding(capacity: Option<usize>, _padding: P) -> SingleLockLinkedBlockingQueue<T, P> {
+        let capacity = capacity.unwrap_or(usize::MAX);
+        assert!(capacity > 0, "capacity must be positive");
+        let sentinel = Node::new(None);
+        SingleLockLinkedBlockingQueue {
+            links: Mutex::new(Links { head: CachePadded::new(sentinel), tail: CachePadded::new(sentinel), size: 0 }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity: capacity
+        }
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        if self.capacity == usize::MAX {
+            None
+        } else {
+            Some(self.capacity)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.lock().unwrap().size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn enqueue(&self, item: T) {
+        if self.offer(item, None).is_err() {
+            unreachable!();
+        }
+    }
+
+    pub fn try_enqueue(&self, item: T) -> Result<(), Full<T>> {
+        self.offer(item, Some(Instant::now()))
+    }
+
+    pub fn enqueue_timeout(&self, item: T, timeout: Duration) -> Result<(), Full<T>> {
+        self.offer(item, Some(Instant::now() + timeout))
+    }
+
+    pub fn deque(&self) -> T {
+        self.poll(None).unwrap()
+    }
+
+    pub fn try_deque(&self) -> Option<T> {
+        self.poll(Some(Instant::now()))
+    }
+
+    pub fn deque_timeout(&self, timeout: Duration) -> Option<T> {
+        self.poll(Some(Instant::now() + timeout))
+    }
+
+    fn offer(&self, item: T, deadline: Option<Instant>) -> Result<(), Full<T>> {
+        let capacity = self.capacity;
+        let links = self.links.lock().unwrap();
+        match wait_while(&self.not_full, links, deadline, |links| links.size == capacity) {
+            Some(mut links) => {
+                links.push(item);
+                self.not_empty.notify_one();
+                Ok(())
+            }
+            None => Err(Full(item))
+        }
+    }
+
+    fn poll(&self, deadline: Option<Instant>) -> Option<T> {
+        let links = self.links.lock().unwrap();
+        match wait_while(&self.not_empty, links, deadline, |links| links.size == 0) {
+            Some(mut links) => {
+                let item = links.pop();
+                self.not_full.notify_one();
+                item
+            }
+            None => None
+        }
+    }
+}
+
+impl <T, P: Padding> ConcurrentQueue<T> for SingleLockLinkedBlockingQueue<T, P> {
+    fn enqueue(&self, item: T) {
+        SingleLockLinkedBlockingQueue::enqueue(self, item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        self.try_deque()
+    }
+}
+
+// enqueuers and dequers never share a lock, the atomic size is the only thing
+// that makes a linked node visible to dequers and a freed slot visible to enqueuers
+pub struct DoubleLockLinkedBlockingQueue<T, P: Padding = NoPadding> {
+    head: CachePadded<Mutex<*mut Node<T>>, P>,
+    not_empty: Condvar,
+    tail: CachePadded<Mutex<*mut Node<T>>, P>,
+    not_full: Condvar,
+    size: CachePadded<AtomicUsize, P>,
+    capacity: usize
+}
+
+unsafe impl <T: Send, P: Padding> Send for DoubleLockLinkedBlockingQueue<T, P> {}
+unsafe impl <T: Send, P: Padding> Sync for DoubleLockLinkedBlockingQueue<T, P> {}
+
+pub type PaddedDoubleLockLinkedBlockingQueue<T> = DoubleLockLinkedBlockingQueue<T, CacheLine>;
+
+impl <T> DoubleLockLinkedBlockingQueue<T> {
+    pub fn new() -> DoubleLockLinkedBlockingQueue<T> {
+        DoubleLockLinkedBlockingQueue::with_padding(None, NoPadding)
+    }
+
+    pub fn with_capacity(capacity: usize) -> DoubleLockLinkedBlockingQueue<T> {
+        DoubleLockLinkedBlockingQueue::with_padding(Some(capacity), NoPadding)
+    }
+}
+
+impl <T, P: Padding> DoubleLockLinkedBlockingQueue<T, P> {
+    pub fn with_padding(capacity: Option<usize>, _padding: P) -> DoubleLockLinkedBlockingQueue<T, P> {
+        let capacity = capacity.unwrap_or(usize::MAX);
+        assert!(capacity > 0, "capacity must be positive");
+        let sentinel = Node::new(None);
+        DoubleLockLinkedBlockingQueue {
+            head: CachePadded::new(Mutex::new(sentinel)),
+            not_empty: Condvar::new(),
+            tail: CachePadded::new(Mutex::new(sentinel)),
+            not_full: Condvar::new(),
+            size: CachePadded::new(AtomicUsize::new(0)),
+            capacity: capacity
+        }
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        if self.capacity == usize::MAX {
+            None
+        } else {
+            Some(self.capacity)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn enqueue(&self, item: T) {
+        if self.offer(item, None).is_err() {
+            unreachable!();
+        }
+    }
+
+    pub fn try_enqueue(&self, item: T) -> Result<(), Full<T>> {
+        self.offer(item, Some(Instant::now()))
+    }
+
+    pub fn enqueue_timeout(&self, item: T, timeout: Duration) -> Result<(), Full<T>> {
+        self.offer(item, Some(Instant::now() + timeout))
+    }
+
+    pub fn deque(&self) -> T {
+        self.poll(None).unwrap()
+    }
+
+    pub fn try_deque(&self) -> Option<T> {
+        self.poll(Some(Instant::now()))
+    }
+
+    pub fn deque_timeout(&self, timeout: Duration) -> Option<T> {
+        self.poll(Some(Instant::now() + timeout))
+    }
+
+    fn offer(&self, item: T, deadline: Option<Instant>) -> Result<(), Full<T>> {
+        let count = {
+            let tail = self.tail.lock().unwrap();
+            let mut tail = match wait_while(&self.not_full, tail, deadline, |_| self.len() == self.capacity) {
+                Some(tail) => tail,
+                None => return Err(Full(item))
+            };
+            let node = Node::new(Some(item));
+            unsafe { (**tail).next = node; }
+            *tail = node;
+            let count = self.size.fetch_add(1, Ordering::SeqCst);
+            // other enqueuers are woken by the one that got in, not by dequers
+            if count + 1 < self.capacity {
+                self.not_full.notify_one();
+            }
+            count
+        };
+        if count == 0 {
+            let _head = self.head.lock().unwrap();
+            self.not_empty.notify_one();
+        }
+        Ok(())
+    }
+
+    fn poll(&self, deadline: Option<Instant>) -> Option<T> {
+        let (item, count) = {
+            let head = self.head.lock().unwrap();
+            let mut head = match wait_while(&self.not_empty, head, deadline, |_| self.len() == 0) {
+                Some(head) => head,
+                None => return None
+            };
+            let item = unsafe {
+                let first = *head;
+                let next = (*first).next;
+                *head = next;
+                drop(Box::from_raw(first));
+                (*next).item.take()
+            };
+            let count = self.size.fetch_sub(1, Ordering::SeqCst);
+            // other dequers are woken by the one that got in, not by enqueuers
+            if count > 1 {
+                self.not_empty.notify_one();
+            }
+            (item, count)
+        };
+        if count == self.capacity {
+            let _tail = self.tail.lock().unwrap();
+            self.not_full.notify_one();
+        }
+        item
+    }
+}
+
+impl <T, P: Padding> ConcurrentQueue<T> for DoubleLockLinkedBlockingQueue<T, P> {
+    fn enqueue(&self, item: T) {
+        DoubleLockLinkedBlockingQueue::enqueue(self, item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        self.try_deque()
+    }
+}
+
+impl <T, P: Padding> Drop for DoubleLockLinkedBlockingQueue<T, P> {
+    fn drop(&mut self) {
+        unsafe { free_nodes(*self.head.get_mut().unwrap()); }
+    }
+}
+
+// the ring of a non resizable array queue guarded by a single lock
+pub struct ArrayBlockingQueue<T, P: Padding = NoPadding> {
+    ring: CachePadded<Mutex<NonResizableArrayQueue<T>>, P>,
+    not_empty: Condvar,
+    not_full: Condvar
+}
+
+pub type PaddedArrayBlockingQueue<T> = ArrayBlockingQueue<T, CacheLine>;
+
+impl <T> ArrayBlockingQueue<T> {
+    pub fn new(capacity: usize) -> ArrayBlockingQueue<T> {
+        ArrayBlockingQueue::with_padding(capacity, NoPadding)
+    }
+}
+
+impl <T, P: Padding> ArrayBlockingQueue<T, P> {
+    pub fn with_padding(capacity: usize, _padding: P) -> ArrayBlockingQueue<T, P> {
+        ArrayBlockingQueue {
+            ring: CachePadded::new(Mutex::new(NonResizableArrayQueue::with_policy(capacity, OverflowPolicy::Reject))),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new()
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.lock().unwrap().capacity()
+    }
+
+    pub fn remaining_capacity(&self) -> usize {
+        let ring = self.ring.lock().unwrap();
+        ring.capacity() - ring.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn put(&self, item: T) {
+        if self.insert(item, None).is_err() {
+            unreachable!();
+        }
+    }
+
+    pub fn offer(&self, item: T, timeout: Duration) -> Result<(), Full<T>> {
+        self.insert(item, Some(Instant::now() + timeout))
+    }
+
+    pub fn take(&self) -> T {
+        self.remove(None).unwrap()
+    }
+
+    pub fn poll(&self, timeout: Duration) -> Option<T> {
+        self.remove(Some(Instant::now() + timeout))
+    }
+
+    // moves up to max items without waiting, returns how many were moved
+    pub fn drain_to(&self, items: &mut Vec<T>, max: usize) -> usize {
+        let count = self.ring.lock().unwrap().deque_into(items, max);
+        if count > 0 {
+            self.not_full.notify_all();
+        }
+        count
+    }
+
+    fn insert(&self, item: T, deadline: Option<Instant>) -> Result<(), Full<T>> {
+        let ring = self.ring.lock().unwrap();
+        match wait_while(&self.not_full, ring, deadline, |ring| ring.is_full()) {
+            Some(mut ring) => {
+                let inserted = ring.try_enqueue(item);
+                self.not_empty.notify_one();
+                inserted
+            }
+            None => Err(Full(item))
+        }
+    }
+
+    fn remove(&self, deadline: Option<Instant>) -> Option<T> {
+        let ring = self.ring.lock().unwrap();
+        match wait_while(&self.not_empty, ring, deadline, |ring| ring.is_empty()) {
+            Some(mut ring) => {
+                let item = ring.deque();
+                self.not_full.notify_one();
+                item
+            }
+            None => None
+        }
+    }
+}
+
+impl <T, P: Padding> ConcurrentQueue<T> for ArrayBlockingQueue<T, P> {
+    fn enqueue(&self, item: T) {
+        self.put(item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        self.poll(Duration::from_millis(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod single_lock_linked_blocking_queue {
+        use std::rc::Rc;
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        use super::super::super::padding::CacheLine;
+        use super::super::SingleLockLinkedBlockingQueue;
+        use super::super::super::stress;
+        use super::super::super::super::Full;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let queue: SingleLockLinkedBlockingQueue<i32> = SingleLockLinkedBlockingQueue::new();
+
+            assert_eq!(queue.try_deque(), None);
+            assert!(queue.is_empty());
+            assert_eq!(queue.capacity(), None);
+        }
+
+        #[test]
+        fn enqueue_many_items() {
+            let queue = SingleLockLinkedBlockingQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert_eq!(queue.len(), 3);
+            assert_eq!(queue.deque(), 10);
+            assert_eq!(queue.deque(), 20);
+            assert_eq!(queue.deque(), 30);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn try_enqueue_into_full_queue() {
+            let queue = SingleLockLinkedBlockingQueue::with_capacity(2);
+
+            assert_eq!(queue.try_enqueue(10), Ok(()));
+            assert_eq!(queue.try_enqueue(20), Ok(()));
+            assert_eq!(queue.try_enqueue(30), Err(Full(30)));
+
+            assert_eq!(queue.capacity(), Some(2));
+            assert_eq!(queue.try_deque(), Some(10));
+            assert_eq!(queue.try_enqueue(30), Ok(()));
+        }
+
+        #[test]
+        fn deque_timeout_from_empty_queue() {
+            let queue: SingleLockLinkedBlockingQueue<i32> = SingleLockLinkedBlockingQueue::new();
+            let start = Instant::now();
+
+            assert_eq!(queue.deque_timeout(Duration::from_millis(20)), None);
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+
+        #[test]
+        fn enqueue_timeout_into_full_queue() {
+            let queue = SingleLockLinkedBlockingQueue::with_capacity(1);
+            queue.enqueue(10);
+            let start = Instant::now();
+
+            assert_eq!(queue.enqueue_timeout(20, Duration::from_millis(20)), Err(Full(20)));
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+
+        #[test]
+        fn deque_waits_for_enqueued_item() {
+            let queue = Arc::new(SingleLockLinkedBlockingQueue::new());
+
+            let writer = {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(20));
+                    queue.enqueue(10);
+                })
+            };
+
+            assert_eq!(queue.deque(), 10);
+            writer.join().unwrap();
+        }
+
+        #[test]
+        fn enqueue_waits_for_free_slot() {
+            let queue = Arc::new(SingleLockLinkedBlockingQueue::with_capacity(1));
+            queue.enqueue(10);
+
+            let writer = {
+                let queue = queue.clone();
+                thread::spawn(move || queue.enqueue(20))
+            };
+
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(queue.len(), 1);
+            assert_eq!(queue.deque(), 10);
+            writer.join().unwrap();
+            assert_eq!(queue.deque(), 20);
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = SingleLockLinkedBlockingQueue::new();
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                stress::publish_subscribe(SingleLockLinkedBlockingQueue::new(), readers, writers);
+                stress::publish_subscribe(SingleLockLinkedBlockingQueue::with_capacity(64), readers, writers);
+                stress::publish_subscribe(SingleLockLinkedBlockingQueue::with_padding(Some(64), CacheLine), readers, writers);
+            }
+        }
+    }
+
+    mod double_lock_linked_blocking_queue {
+        use std::rc::Rc;
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        use super::super::super::padding::CacheLine;
+        use super::super::DoubleLockLinkedBlockingQueue;
+        use super::super::super::stress;
+        use super::super::super::super::Full;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let queue: DoubleLockLinkedBlockingQueue<i32> = DoubleLockLinkedBlockingQueue::new();
+
+            assert_eq!(queue.try_deque(), None);
+            assert!(queue.is_empty());
+            assert_eq!(queue.capacity(), None);
+        }
+
+        #[test]
+        fn enqueue_many_items() {
+            let queue = DoubleLockLinkedBlockingQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert_eq!(queue.len(), 3);
+            assert_eq!(queue.deque(), 10);
+            assert_eq!(queue.deque(), 20);
+            assert_eq!(queue.deque(), 30);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn try_enqueue_into_full_queue() {
+            let queue = DoubleLockLinkedBlockingQueue::with_capacity(2);
+
+            assert_eq!(queue.try_enqueue(10), Ok(()));
+            assert_eq!(queue.try_enqueue(20), Ok(()));
+            assert_eq!(queue.try_enqueue(30), Err(Full(30)));
+
+            assert_eq!(queue.capacity(), Some(2));
+            assert_eq!(queue.try_deque(), Some(10));
+            assert_eq!(queue.try_enqueue(30), Ok(()));
+        }
+
+        #[test]
+        fn deque_timeout_from_empty_queue() {
+            let queue: DoubleLockLinkedBlockingQueue<i32> = DoubleLockLinkedBlockingQueue::new();
+            let start = Instant::now();
+
+            assert_eq!(queue.deque_timeout(Duration::from_millis(20)), None);
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+
+        #[test]
+        fn enqueue_timeout_into_full_queue() {
+            let queue = DoubleLockLinkedBlockingQueue::with_capacity(1);
+            queue.enqueue(10);
+            let start = Instant::now();
+
+            assert_eq!(queue.enqueue_timeout(20, Duration::from_millis(20)), Err(Full(20)));
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+
+        #[test]
+        fn deque_waits_for_enqueued_item() {
+            let queue = Arc::new(DoubleLockLinkedBlockingQueue::new());
+
+            let writer = {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(20));
+                    queue.enqueue(10);
+                })
+            };
+
+            assert_eq!(queue.deque(), 10);
+            writer.join().unwrap();
+        }
+
+        #[test]
+        fn enqueue_waits_for_free_slot() {
+            let queue = Arc::new(DoubleLockLinkedBlockingQueue::with_capacity(1));
+            queue.enqueue(10);
+
+            let writer = {
+                let queue = queue.clone();
+                thread::spawn(move || queue.enqueue(20))
+            };
+
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(queue.len(), 1);
+            assert_eq!(queue.deque(), 10);
+            writer.join().unwrap();
+            assert_eq!(queue.deque(), 20);
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = DoubleLockLinkedBlockingQueue::new();
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                stress::publish_subscribe(DoubleLockLinkedBlockingQueue::new(), readers, writers);
+                stress::publish_subscribe(DoubleLockLinkedBlockingQueue::with_capacity(64), readers, writers);
+                stress::publish_subscribe(DoubleLockLinkedBlockingQueue::with_padding(Some(64), CacheLine), readers, writers);
+            }
+        }
+    }
+
+    mod array_blocking_queue {
+        use std::rc::Rc;
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        use super::super::super::padding::CacheLine;
+        use super::super::ArrayBlockingQueue;
+        use super::super::super::stress;
+        use super::super::super::super::Full;
+
+        #[test]
+        fn poll_from_empty_queue() {
+            let queue: ArrayBlockingQueue<i32> = ArrayBlockingQueue::new(16);
+
+            assert_eq!(queue.poll(Duration::from_millis(0)), None);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn put_many_items() {
+            let queue = ArrayBlockingQueue::new(16);
+
+            queue.put(10);
+            queue.put(20);
+            queue.put(30);
+
+            assert_eq!(queue.len(), 3);
+            assert_eq!(queue.remaining_capacity(), 13);
+            assert_eq!(queue.take(), 10);
+            assert_eq!(queue.take(), 20);
+            assert_eq!(queue.take(), 30);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn capacity_is_rounded_to_power_of_two() {
+            let queue: ArrayBlockingQueue<i32> = ArrayBlockingQueue::new(5);
+
+            assert_eq!(queue.capacity(), 8);
+        }
+
+        #[test]
+        fn offer_into_full_queue() {
+            let queue = ArrayBlockingQueue::new(2);
+            queue.put(10);
+            queue.put(20);
+            let start = Instant::now();
+
+            assert_eq!(queue.offer(30, Duration::from_millis(20)), Err(Full(30)));
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+
+        #[test]
+        fn poll_timeout_from_empty_queue() {
+            let queue: ArrayBlockingQueue<i32> = ArrayBlockingQueue::new(2);
+            let start = Instant::now();
+
+            assert_eq!(queue.poll(Duration::from_millis(20)), None);
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        }
+
+        #[test]
+        fn take_waits_for_put_item() {
+            let queue = Arc::new(ArrayBlockingQueue::new(2));
+
+            let writer = {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(20));
+                    queue.put(10);
+                })
+            };
+
+            assert_eq!(queue.take(), 10);
+            writer.join().unwrap();
+        }
+
+        #[test]
+        fn put_waits_for_free_slot() {
+            let queue = Arc::new(ArrayBlockingQueue::new(1));
+            queue.put(10);
+
+            let writer = {
+                let queue = queue.clone();
+                thread::spawn(move || queue.put(20))
+            };
+
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(queue.len(), 1);
+            assert_eq!(queue.take(), 10);
+            writer.join().unwrap();
+            assert_eq!(queue.take(), 20);
+        }
+
+        #[test]
+        fn drain_to_vector() {
+            let queue = ArrayBlockingQueue::new(4);
+            let mut items = vec![0];
+
+            for item in 1..5 {
+                queue.put(item);
+            }
+
+            assert_eq!(queue.drain_to(&mut items, 3), 3);
+            assert_eq!(items, vec![0, 1, 2, 3]);
+            assert_eq!(queue.drain_to(&mut items, 3), 1);
+            assert_eq!(items, vec![0, 1, 2, 3, 4]);
+            assert_eq!(queue.drain_to(&mut items, 3), 0);
+        }
+
+        #[test]
+        fn drain_to_unblocks_waiting_writers() {
+            let queue = Arc::new(ArrayBlockingQueue::new(2));
+            queue.put(10);
+            queue.put(20);
+
+            let writers = (0..2).map(|writer| {
+                let queue = queue.clone();
+                thread::spawn(move || queue.put(30 + writer))
+            }).collect::<Vec<_>>();
+
+            thread::sleep(Duration::from_millis(20));
+            let mut items = vec![];
+            assert_eq!(queue.drain_to(&mut items, 2), 2);
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            assert_eq!(queue.len(), 2);
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = ArrayBlockingQueue::new(16);
+
+                for _ in 0..10 {
+                    queue.put(item.clone());
+                }
+
+                queue.take();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                stress::publish_subscribe(ArrayBlockingQueue::new(64), readers, writers);
+                stress::publish_subscribe(ArrayBlockingQueue::with_padding(64, CacheLine), readers, writers);
+            }
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{self, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use super::{Reclaimer, ReclaimGuard};
+use super::padding::CachePadded;
+
+const BAG_CAPACITY: usize = 64;
+const PINS_BETWEEN_COLLECTS: usize = 128;
+
+// garbage sealed at epoch e is freed once the global epoch reaches e + 2,
+// because every thread that was pinned at e or before has been unpinned by then
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+static PARTICIPANTS: AtomicPtr<Participant> = AtomicPtr::new(0 as *mut Participant);
+static ORPHANS: AtomicPtr<Orphan> = AtomicPtr::new(0 as *mut Orphan);
+
+// the state of a participant is 0 when it is not pinned and (epoch << 1) | 1 when it is,
+// participants are never freed, a thread that exits leaves its participant for the next one
+struct Participant {
+    state: CachePadded<AtomicUsize>,
+    in_use: AtomicBool,
+    next: *mut Participant
+}
+
+struct Deferred {
+    ptr: *mut u8,
+    free: unsafe fn(*mut u8)
+}
+
+unsafe impl Send for Deferred {}
+
+unsafe fn free_box<T>(ptr: *mut u8) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+impl Deferred {
+    unsafe fn call(self) {
+        (self.free)(self.ptr)
+    }
+}
+
+struct Bag {
+    epoch: usize,
+    items: Vec<Deferred>
+}
+
+impl Bag {
+    unsafe fn free(self) {
+        for deferred in self.items {
+            deferred.call();
+        }
+    }
+}
+
+// bags of exited threads wait here until a live thread adopts them
+struct Orphan {
+    bag: Bag,
+    next: *mut Orphan
+}
+
+struct Local {
+    participant: &'static Participant,
+    guards: Cell<usize>,
+    pins: Cell<usize>,
+    bag: RefCell<Vec<Deferred>>,
+    sealed: RefCell<VecDeque<Bag>>
+}
+
+thread_local! {
+    static LOCAL: Local = Local::register();
+}
+
+impl Local {
+    fn register() -> Local {
+        Local {
+            participant: acquire_participant(),
+            guards: Cell::new(0),
+            pins: Cell::new(0),
+            bag: RefCell::new(Vec::with_capacity(BAG_CAPACITY)),
+            sealed: RefCell::new(VecDeque::new())
+        }
+    }
+
+    fn pin(&self) {
+        let guards = self.guards.get();
+        self.guards.set(guards + 1);
+        if guards == 0 {
+            let epoch = EPOCH.load(Ordering::SeqCst);
+            self.participant.state.store((epoch << 1) | 1, Ordering::SeqCst);
+            atomic::fence(Ordering::SeqCst);
+
+            let pins = self.pins.get().wrapping_add(1);
+            self.pins.set(pins);
+            if pins % PINS_BETWEEN_COLLECTS == 0 {
+                try_advance();
+                self.collect();
+            }
+        }
+    }
+
+    fn unpin(&self) {
+        let guards = self.guards.get();
+        self.guards.set(guards - 1);
+        if guards == 1 {
+            self.participant.state.store(0, Ordering::SeqCst);
+        }
+    }
+
+    fn defer(&self, deferred: Deferred) {
+        let full = {
+            let mut bag = self.bag.borrow_mut();
+            bag.push(deferred);
+            bag.len() >= BAG_CAPACITY
+        };
+        if full {
+            self.seal();
+            try_advance();
+            self.collect();
+        }
+    }
+
+    fn seal(&self) {
+        let items = mem::replace(&mut *self.bag.borrow_mut(), Vec::with_capacity(BAG_CAPACITY));
+        if !items.is_empty() {
+            let bag = Bag { epoch: EPOCH.load(Ordering::SeqCst), items: items };
+            self.sealed.borrow_mut().push_back(bag);
+        }
+    }
+
+    // destructors may pin and defer again, so no borrow is held while they run
+    fn collect(&self) {
+        self.adopt_orphans();
+        let epoch = EPOCH.load(Ordering::SeqCst);
+        loop {
+            let bag = {
+                let mut sealed = self.sealed.borrow_mut();
+                match sealed.front() {
+                    Some(bag) if epoch.wrapping_sub(bag.epoch) >= 2 => sealed.pop_front(),
+                    _ => None
+                }
+            };
+            match bag {
+                Some(bag) => unsafe { bag.free() },
+                None => break
+            }
+        }
+    }
+
+    fn adopt_orphans(&self) {
+        let mut orphan = ORPHANS.swap(ptr::null_mut(), Ordering::SeqCst);
+        while !orphan.is_null() {
+            let adopted = unsafe { Box::from_raw(orphan) };
+            orphan = adopted.next;
+            let mut sealed = self.sealed.borrow_mut();
+            // keeps the oldest bags in front where collect looks for them
+            let position = sealed.iter()
+                .position(|bag| bag.epoch.wrapping_sub(adopted.bag.epoch) as isize > 0)
+                .unwrap_or(sealed.len());
+            sealed.insert(position, adopted.bag);
+        }
+    }
+}
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        self.seal();
+        for bag in self.sealed.borrow_mut().drain(..) {
+            let orphan = Box::into_raw(Box::new(Orphan { bag: bag, next: ptr::null_mut() }));
+            let mut head = ORPHANS.load(Ordering::SeqCst);
+            loop {
+                unsafe { (*orphan).next = head; }
+                match ORPHANS.compare_exchange(head, orphan, Ordering::SeqCst, Ordering::SeqCst) {
+                    Ok(_) => break,
+                    Err(current) => head = current
+                }
+            }
+        }
+        self.participant.state.store(0, Ordering::SeqCst);
+        self.participant.in_use.store(false, Ordering::SeqCst);
+    }
+}
+
+fn acquire_participant() -> &'static Participant {
+    let mut participant = PARTICIPANTS.load(Ordering::SeqCst);
+    while !participant.is_null() {
+        let reused = unsafe { &*participant };
+        if reused.in_use.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+            return reused;
+        }
+        participant = reused.next;
+    }
+    let participant = Box::into_raw(Box::new(Participant {
+        state: CachePadded::new(AtomicUsize::new(0)),
+        in_use: AtomicBool::new(true),
+        next: ptr::null_mut()
+    }));
+    let mut head = PARTICIPANTS.load(Ordering::SeqCst);
+    loop {
+        unsafe { (*participant).next = head; }
+        match PARTICIPANTS.compare_exchange(head, participant, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => return unsafe { &*participant },
+            Err(current) => head = current
+        }
+    }
+}
+
+// the epoch moves on only when every pinned participant has seen the current one
+fn try_advance() -> bool {
+    let epoch = EPOCH.load(Ordering::SeqCst);
+    atomic::fence(Ordering::SeqCst);
+    let mut participant = PARTICIPANTS.load(Ordering::SeqCst);
+    while !participant.is_null() {
+        let current = unsafe { &*participant };
+        let state = current.state.load(Ordering::SeqCst);
+        if state & 1 == 1 && state >> 1 != epoch {
+            return false;
+        }
+        participant = current.next;
+    }
+    EPOCH.compare_exchange(epoch, epoch.wrapping_add(1), Ordering::SeqCst, Ordering::SeqCst).is_ok()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Epoch;
+
+impl Reclaimer for Epoch {
+    type Guard = Guard;
+
+    fn pin() -> Guard {
+        pin()
+    }
+}
+
+// while a guard is alive the thread is pinned, nothing retired after the pin is freed under it
+pub struct Guard {
+    _marker: PhantomData<*const ()>
+}
+
+pub fn pin() -> Guard {
+    LOCAL.with(|local| local.pin());
+    Guard { _marker: PhantomData }
+}
+
+pub fn is_pinned() -> bool {
+    LOCAL.with(|local| local.guards.get() > 0)
+}
+
+impl Guard {
+    // the pointer must come from Box::into_raw and be unreachable for threads that pin later
+    pub unsafe fn defer_free<T>(&self, ptr: *mut T) {
+        LOCAL.with(|local| local.defer(Deferred { ptr: ptr as *mut u8, free: free_box::<T> }));
+    }
+
+    // seals the garbage of this thread and frees whatever is old enough
+    pub fn flush(&self) {
+        LOCAL.with(|local| {
+            local.seal();
+            try_advance();
+            local.collect();
+        });
+    }
+}
+
+// a pinned thread protects everything it can reach, so protecting is a plain load
+impl ReclaimGuard for Guard {
+    fn protect<T>(&mut self, _index: usize, source: &AtomicPtr<T>) -> *mut T {
+        source.load(Ordering::SeqCst)
+    }
+
+    unsafe fn retire<T>(&self, ptr: *mut T) {
+        self.defer_free(ptr)
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        LOCAL.with(|local| local.unpin());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod epoch {
+        use std::sync::{Arc, Barrier};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::thread;
+
+        use super::super::{is_pinned, pin};
+
+        struct Counted(Arc<AtomicUsize>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        fn defer_counted(freed: &Arc<AtomicUsize>) {
+            let guard = pin();
+            unsafe { guard.defer_free(Box::into_raw(Box::new(Counted(freed.clone())))); }
+        }
+
+        fn flush_until(freed: &Arc<AtomicUsize>, expected: usize) {
+            for _ in 0..100000 {
+                if freed.load(Ordering::SeqCst) == expected {
+                    return;
+                }
+                pin().flush();
+                thread::yield_now();
+            }
+            assert_eq!(freed.load(Ordering::SeqCst), expected);
+        }
+
+        #[test]
+        fn pin_is_reentrant() {
+            assert!(!is_pinned());
+            {
+                let _outer = pin();
+                {
+                    let _inner = pin();
+                    assert!(is_pinned());
+                }
+                assert!(is_pinned());
+            }
+            assert!(!is_pinned());
+        }
+
+        #[test]
+        fn deferred_garbage_is_freed() {
+            let freed = Arc::new(AtomicUsize::new(0));
+
+            defer_counted(&freed);
+
+            flush_until(&freed, 1);
+        }
+
+        #[test]
+        fn pinned_thread_holds_garbage_back() {
+            let freed = Arc::new(AtomicUsize::new(0));
+            let pinned = Arc::new(Barrier::new(2));
+            let release = Arc::new(Barrier::new(2));
+
+            let reader = {
+                let pinned = pinned.clone();
+                let release = release.clone();
+                thread::spawn(move || {
+                    let _guard = pin();
+                    pinned.wait();
+                    release.wait();
+                })
+            };
+
+            pinned.wait();
+            defer_counted(&freed);
+            for _ in 0..100 {
+                pin().flush();
+            }
+            assert_eq!(freed.load(Ordering::SeqCst), 0);
+
+            release.wait();
+            reader.join().unwrap();
+            flush_until(&freed, 1);
+        }
+
+        #[test]
+        fn garbage_of_exited_thread_is_adopted() {
+            let freed = Arc::new(AtomicUsize::new(0));
+
+            {
+                let freed = freed.clone();
+                thread::spawn(move || defer_counted(&freed)).join().unwrap();
+            }
+
+            flush_until(&freed, 1);
+        }
+
+        #[test]
+        fn many_threads_defer_garbage() {
+            const THREADS: usize = 8;
+            const ITEMS: usize = 10000;
+            let freed = Arc::new(AtomicUsize::new(0));
+
+            let handles = (0..THREADS).map(|_| {
+                let freed = freed.clone();
+                thread::spawn(move || {
+                    for _ in 0..ITEMS {
+                        defer_counted(&freed);
+                    }
+                })
+            }).collect::<Vec<_>>();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            flush_until(&freed, THREADS * ITEMS);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::cell::{Cell, RefCell};
+use std::cmp;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{self, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use super::{Reclaimer, ReclaimGuard};
+use super::padding::CachePadded;
+
+pub const HAZARDS_PER_THREAD: usize = 8;
+pub const HAZARDS_PER_GUARD: usize = 2;
+const MIN_SCAN_THRESHOLD: usize = 64;
+
+static RECORDS: AtomicPtr<Record> = AtomicPtr::new(0 as *mut Record);
+static RECORDS_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ORPHANS: AtomicPtr<Orphan> = AtomicPtr::new(0 as *mut Orphan);
+
+// every thread publishes the pointers it is about to dereference in its own record,
+// records are never freed, a thread that exits leaves its record for the next one
+struct Record {
+    hazards: CachePadded<[AtomicPtr<u8>; HAZARDS_PER_THREAD]>,
+    in_use: AtomicBool,
+    next: *mut Record
+}
+
+struct Retired {
+    ptr: *mut u8,
+    free: unsafe fn(*mut u8)
+}
+
+unsafe impl Send for Retired {}
+
+unsafe fn free_box<T>(ptr: *mut u8) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+// retired pointers of exited threads wait here until a live thread scans them
+struct Orphan {
+    retired: Vec<Retired>,
+    next: *mut Orphan
+}
+
+struct Local {
+    record: &'static Record,
+    // a bit for every block of hazards taken by a live guard
+    used: Cell<usize>,
+    retired: RefCell<Vec<Retired>>
+}
+
+thread_local! {
+    static LOCAL: Local = Local::register();
+}
+
+impl Local {
+    fn register() -> Local {
+        Local {
+            record: acquire_record(),
+            used: Cell::new(0),
+            retired: RefCell::new(vec![])
+        }
+    }
+
+    fn retire(&self, retired: Retired) {
+        let pending = {
+            let mut list = self.retired.borrow_mut();
+            list.push(retired);
+            list.len()
+        };
+        if pending >= scan_threshold() {
+            self.scan();
+        }
+    }
+
+    // frees every retired pointer that no thread has published as a hazard,
+    // destructors may retire again, so no borrow is held while they run
+    fn scan(&self) {
+        self.adopt_orphans();
+        atomic::fence(Ordering::SeqCst);
+        let mut hazards = vec![];
+        let mut record = RECORDS.load(Ordering::SeqCst);
+        while !record.is_null() {
+            let current = unsafe { &*record };
+            for hazard in current.hazards.iter() {
+                let hazard = hazard.load(Ordering::SeqCst);
+                if !hazard.is_null() {
+                    hazards.push(hazard);
+                }
+            }
+            record = current.next;
+        }
+        hazards.sort();
+
+        let retired = mem::replace(&mut *self.retired.borrow_mut(), vec![]);
+        let (protected, unprotected): (Vec<Retired>, Vec<Retired>) = retired.into_iter()
+            .partition(|retired| hazards.binary_search(&retired.ptr).is_ok());
+        self.retired.borrow_mut().extend(protected);
+        for retired in unprotected {
+            unsafe { (retired.free)(retired.ptr); }
+        }
+    }
+
+    fn adopt_orphans(&self) {
+        let mut orphan = ORPHANS.swap(ptr::null_mut(), Ordering::SeqCst);
+        while !orphan.is_null() {
+            let adopted = unsafe { Box::from_raw(orphan) };
+            orphan = adopted.next;
+            self.retired.borrow_mut().extend(adopted.retired);
+        }
+    }
+}
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        self.scan();
+        let retired = mem::replace(&mut *self.retired.borrow_mut(), vec![]);
+        if !retired.is_empty() {
+            let orphan = Box::into_raw(Box::new(Orphan { retired: retired, next: ptr::null_mut() }));
+            let mut head = ORPHANS.load(Ordering::SeqCst);
+            loop {
+                unsafe { (*orphan).next = head; }
+                match ORPHANS.compare_exchange(head, orphan, Ordering::SeqCst, Ordering::SeqCst) {
+                    Ok(_) => break,
+                    Err(current) => head = current
+                }
+            }
+        }
+        for hazard in self.record.hazards.iter() {
+            hazard.store(ptr::null_mut(), Ordering::SeqCst);
+        }
+        self.record.in_use.store(false, Ordering::SeqCst);
+    }
+}
+
+fn acquire_record() -> &'static Record {
+    let mut record = RECORDS.load(Ordering::SeqCst);
+    while !record.is_null() {
+        let reused = unsafe { &*record };
+        if reused.in_use.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+            return reused;
+        }
+        record = reused.next;
+    }
+    let record = Box::into_raw(Box::new(Record {
+        hazards: CachePadded::new(Default::default()),
+        in_use: AtomicBool::new(true),
+        next: ptr::null_mut()
+    }));
+    RECORDS_COUNT.fetch_add(1, Ordering::SeqCst);
+    let mut head = RECORDS.load(Ordering::SeqCst);
+    loop {
+        unsafe { (*record).next = head; }
+        match RECORDS.compare_exchange(head, record, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => return unsafe { &*record },
+            Err(current) => head = current
+        }
+    }
+}
+
+// scanning costs a pass over all hazards, so a thread keeps at least twice
+// as many retired pointers as there can be hazards before it scans
+fn scan_threshold() -> usize {
+    cmp::max(MIN_SCAN_THRESHOLD, 2 * HAZARDS_PER_THREAD * RECORDS_COUNT.load(Ordering::Relaxed))
+}
+
+// how many retired pointers of this thread are still waiting to be freed
+pub fn pending() -> usize {
+    LOCAL.with(|local| local.retired.borrow().len())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HazardPointers;
+
+impl Reclaimer for HazardPointers {
+    type Guard = Guard;
+
+    fn pin() -> Guard {
+        protect()
+    }
+}
+
+// owns HAZARDS_PER_GUARD hazards of the thread record, nested guards take other ones
+pub struct Guard {
+    first: usize,
+    _marker: PhantomData<*const ()>
+}
+
+pub fn protect() -> Guard {
+    LOCAL.with(|local| {
+        let used = local.used.get();
+        let block = (0..HAZARDS_PER_THREAD / HAZARDS_PER_GUARD)
+            .find(|block| used & (1 << block) == 0)
+            .expect("too many nested hazard pointer guards");
+        local.used.set(used | (1 << block));
+        Guard { first: block * HAZARDS_PER_GUARD, _marker: PhantomData }
+    })
+}
+
+impl Guard {
+    // frees whatever this thread has retired and nobody protects
+    pub fn flush(&self) {
+        LOCAL.with(|local| local.scan());
+    }
+}
+
+impl ReclaimGuard for Guard {
+    fn protect<T>(&mut self, index: usize, source: &AtomicPtr<T>) -> *mut T {
+        assert!(index < HAZARDS_PER_GUARD, "hazard index {} is out of {}", index, HAZARDS_PER_GUARD);
+        LOCAL.with(|local| {
+            let hazard = &local.record.hazards[self.first + index];
+            let mut ptr = source.load(Ordering::SeqCst);
+            loop {
+                hazard.store(ptr as *mut u8, Ordering::SeqCst);
+                let current = source.load(Ordering::SeqCst);
+                if current == ptr {
+                    return ptr;
+                }
+                ptr = current;
+            }
+        })
+    }
+
+    unsafe fn retire<T>(&self, ptr: *mut T) {
+        LOCAL.with(|local| local.retire(Retired { ptr: ptr as *mut u8, free: free_box::<T> }));
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        LOCAL.with(|local| {
+            for hazard in &local.record.hazards[self.first..self.first + HAZARDS_PER_GUARD] {
+                hazard.store(ptr::null_mut(), Ordering::SeqCst);
+            }
+            local.used.set(local.used.get() & !(1 << (self.first / HAZARDS_PER_GUARD)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod hazard_pointers {
+        use std::sync::{Arc, Barrier};
+        use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+        use std::thread;
+
+        use super::super::{pending, protect, scan_threshold};
+        use super::super::super::ReclaimGuard;
+
+        struct Counted(Arc<AtomicUsize>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        fn counted(freed: &Arc<AtomicUsize>) -> *mut Counted {
+            Box::into_raw(Box::new(Counted(freed.clone())))
+        }
+
+        #[test]
+        fn unprotected_pointer_is_freed_on_flush() {
+            let freed = Arc::new(AtomicUsize::new(0));
+            let guard = protect();
+
+            unsafe { guard.retire(counted(&freed)); }
+            guard.flush();
+
+            assert_eq!(freed.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn protected_pointer_outlives_retire() {
+            let freed = Arc::new(AtomicUsize::new(0));
+            let source = AtomicPtr::new(counted(&freed));
+            let mut reader = protect();
+            let writer = protect();
+
+            let protected = reader.protect(0, &source);
+            unsafe { writer.retire(protected); }
+            writer.flush();
+            assert_eq!(freed.load(Ordering::SeqCst), 0);
+
+            drop(reader);
+            writer.flush();
+            assert_eq!(freed.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        #[should_panic]
+        fn too_many_nested_guards() {
+            let _guards = (0..5).map(|_| protect()).collect::<Vec<_>>();
+        }
+
+        #[test]
+        fn stalled_thread_keeps_garbage_bounded() {
+            const ITEMS: usize = 10000;
+            let freed = Arc::new(AtomicUsize::new(0));
+            let source = Arc::new(AtomicPtr::new(counted(&freed)));
+            let protected = Arc::new(Barrier::new(2));
+            let release = Arc::new(Barrier::new(2));
+
+            let reader = {
+                let source = source.clone();
+                let protected = protected.clone();
+                let release = release.clone();
+                thread::spawn(move || {
+                    let mut guard = protect();
+                    guard.protect(0, &source);
+                    protected.wait();
+                    release.wait();
+                })
+            };
+
+            protected.wait();
+            let guard = protect();
+            unsafe { guard.retire(source.load(Ordering::SeqCst)); }
+            for _ in 0..ITEMS {
+                unsafe { guard.retire(counted(&freed)); }
+                assert!(pending() <= scan_threshold());
+            }
+            guard.flush();
+            assert_eq!(freed.load(Ordering::SeqCst), ITEMS);
+
+            release.wait();
+            reader.join().unwrap();
+            guard.flush();
+            assert_eq!(freed.load(Ordering::SeqCst), ITEMS + 1);
+        }
+
+        #[test]
+        fn garbage_of_exited_thread_is_adopted() {
+            let freed = Arc::new(AtomicUsize::new(0));
+            let source = Arc::new(AtomicPtr::new(counted(&freed)));
+            let mut guard = protect();
+            guard.protect(0, &source);
+
+            {
+                let source = source.clone();
+                thread::spawn(move || {
+                    let guard = protect();
+                    unsafe { guard.retire(source.load(Ordering::SeqCst)); }
+                }).join().unwrap();
+            }
+            assert_eq!(freed.load(Ordering::SeqCst), 0);
+
+            // another thread may adopt the orphan first and free it on its own scan
+            drop(guard);
+            for _ in 0..100000 {
+                if freed.load(Ordering::SeqCst) == 1 {
+                    break;
+                }
+                protect().flush();
+                thread::yield_now();
+            }
+            assert_eq!(freed.load(Ordering::SeqCst), 1);
+        }
+    }
+}
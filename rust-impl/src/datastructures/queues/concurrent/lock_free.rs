@@ -0,0 +1,291 @@
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::{ConcurrentQueue, Reclaimer, ReclaimGuard};
+use super::epoch::Epoch;
+use super::hazard::HazardPointers;
+use super::padding::{CacheLine, CachePadded, NoPadding, Padding};
+
+struct Node<T> {
+    item: Option<T>,
+    next: AtomicPtr<Node<T>>
+}
+
+impl <T> Node<T> {
+    fn new(item: Option<T>) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            item: item,
+            next: AtomicPtr::new(ptr::null_mut())
+        }))
+    }
+}
+
+// every operation runs under a guard of the reclaimer, unlinked nodes are retired
+// to it and freed once no guard can still be looking at them
+pub struct MsQueue<T, P: Padding = NoPadding, R: Reclaimer = Epoch> {
+    head: CachePadded<AtomicPtr<Node<T>>, P>,
+    tail: CachePadded<AtomicPtr<Node<T>>, P>,
+    _reclaimer: PhantomData<R>
+}
+
+unsafe impl <T: Send, P: Padding, R: Reclaimer> Send for MsQueue<T, P, R> {}
+unsafe impl <T: Send, P: Padding, R: Reclaimer> Sync for MsQueue<T, P, R> {}
+
+pub type PaddedMsQueue<T> = MsQueue<T, CacheLine>;
+pub type HazardMsQueue<T> = MsQueue<T, NoPadding, HazardPointers>;
+
+impl <T> MsQueue<T> {
+    pub fn new() -> MsQueue<T> {
+        MsQueue::with_reclaimer(NoPadding, Epoch)
+    }
+}
+
+impl <T, P: Padding> MsQueue<T, P> {
+    pub fn with_padding(padding: P) -> MsQueue<T, P> {
+        MsQueue::with_reclaimer(padding, Epoch)
+    }
+}
+
+impl <T, P: Padding, R: Reclaimer> MsQueue<T, P, R> {
+    pub fn with_reclaimer(_padding: P, _reclaimer: R) -> MsQueue<T, P, R> {
+        let sentinel = Node::new(None);
+        MsQueue {
+            head: CachePadded::new(AtomicPtr::new(sentinel)),
+            tail: CachePadded::new(AtomicPtr::new(sentinel)),
+            _reclaimer: PhantomData
+        }
+    }
+
+    pub fn enqueue(&self, item: T) {
+        let node = Node::new(Some(item));
+        let mut guard = R::pin();
+        loop {
+            let last = guard.protect(0, &self.tail);
+            let next = unsafe { (*last).next.load(Ordering::SeqCst) };
+            if last == self.tail.load(Ordering::SeqCst) {
+                if next.is_null() {
+                    let linked = unsafe {
+                        (*last).next.compare_exchange(next, node, Ordering::SeqCst, Ordering::Relaxed)
+                    };
+                    if linked.is_ok() {
+                        let _ = self.tail.compare_exchange(last, node, Ordering::SeqCst, Ordering::Relaxed);
+                        break;
+                    }
+                } else {
+                    let _ = self.tail.compare_exchange(last, next, Ordering::SeqCst, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    pub fn deque(&self) -> Option<T> {
+        let mut guard = R::pin();
+        loop {
+            let first = guard.protect(0, &self.head);
+            let last = self.tail.load(Ordering::SeqCst);
+            let next = unsafe { guard.protect(1, &(*first).next) };
+            // the head has not moved, so next was not retired before it got protected
+            if first == self.head.load(Ordering::SeqCst) {
+                if first == last {
+                    if next.is_null() {
+                        return None;
+                    }
+                    let _ = self.tail.compare_exchange(last, next, Ordering::SeqCst, Ordering::Relaxed);
+                } else if self.head.compare_exchange(first, next, Ordering::SeqCst, Ordering::Relaxed).is_ok() {
+                    // only the thread that moved the head touches the item of the new sentinel
+                    unsafe {
+                        let item = (*next).item.take();
+                        guard.retire(first);
+                        return item;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let mut guard = R::pin();
+        let first = guard.protect(0, &self.head);
+        unsafe { (*first).next.load(Ordering::SeqCst).is_null() }
+    }
+}
+
+impl <T, P: Padding, R: Reclaimer> ConcurrentQueue<T> for MsQueue<T, P, R> {
+    fn enqueue(&self, item: T) {
+        MsQueue::enqueue(self, item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        MsQueue::deque(self)
+    }
+}
+
+impl <T, P: Padding, R: Reclaimer> Drop for MsQueue<T, P, R> {
+    fn drop(&mut self) {
+        unsafe {
+            let mut node = self.head.load(Ordering::Relaxed);
+            while !node.is_null() {
+                let next = (*node).next.load(Ordering::Relaxed);
+                drop(Box::from_raw(node));
+                node = next;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod ms_queue {
+        use std::rc::Rc;
+
+        use super::super::MsQueue;
+        use super::super::super::padding::CacheLine;
+        use super::super::super::stress;
+
+        const REPETITIONS: usize = 5;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let queue: MsQueue<i32> = MsQueue::new();
+
+            assert_eq!(queue.deque(), None);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn enqueue_item() {
+            let queue = MsQueue::new();
+
+            queue.enqueue(10);
+
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn enqueue_item_many_times() {
+            let queue = MsQueue::new();
+
+            queue.enqueue(10);
+            assert_eq!(queue.deque(), Some(10));
+
+            queue.enqueue(20);
+            assert_eq!(queue.deque(), Some(20));
+
+            queue.enqueue(30);
+            assert_eq!(queue.deque(), Some(30));
+        }
+
+        #[test]
+        fn enqueue_many_items() {
+            let queue = MsQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert!(!queue.is_empty());
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = MsQueue::new();
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn padded_ends_are_on_different_cache_lines() {
+            let queue: MsQueue<i32, CacheLine> = MsQueue::with_padding(CacheLine);
+
+            let head = &*queue.head as *const _ as usize;
+            let tail = &*queue.tail as *const _ as usize;
+
+            assert!(head.max(tail) - head.min(tail) >= 128);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                for _ in 0..REPETITIONS {
+                    stress::publish_subscribe(MsQueue::new(), readers, writers);
+                    stress::publish_subscribe(MsQueue::with_padding(CacheLine), readers, writers);
+                }
+            }
+        }
+    }
+
+    mod hazard_ms_queue {
+        use std::rc::Rc;
+
+        use super::super::{HazardMsQueue, MsQueue};
+        use super::super::super::hazard::HazardPointers;
+        use super::super::super::padding::{CacheLine, NoPadding};
+        use super::super::super::stress;
+
+        const REPETITIONS: usize = 5;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let queue: HazardMsQueue<i32> = HazardMsQueue::with_reclaimer(NoPadding, HazardPointers);
+
+            assert_eq!(queue.deque(), None);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn enqueue_many_items() {
+            let queue = HazardMsQueue::with_reclaimer(NoPadding, HazardPointers);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert!(!queue.is_empty());
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = HazardMsQueue::with_reclaimer(NoPadding, HazardPointers);
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                for _ in 0..REPETITIONS {
+                    stress::publish_subscribe(HazardMsQueue::with_reclaimer(NoPadding, HazardPointers), readers, writers);
+                    stress::publish_subscribe(MsQueue::with_reclaimer(CacheLine, HazardPointers), readers, writers);
+                }
+            }
+        }
+    }
+}
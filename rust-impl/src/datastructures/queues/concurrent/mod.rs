@@ -0,0 +1,98 @@
+pub mod blocking;
+pub mod epoch;
+pub mod hazard;
+pub mod lock_free;
+pub mod mpmc;
+pub mod padding;
+pub mod spsc;
+pub mod work_stealing;
+
+use std::sync::atomic::AtomicPtr;
+
+pub trait ConcurrentQueue<E> {
+    fn enqueue(&self, item: E);
+
+    fn deque(&self) -> Option<E>;
+}
+
+// a memory reclamation scheme that node based queues free their unlinked nodes through
+pub trait Reclaimer {
+    type Guard: ReclaimGuard;
+
+    fn pin() -> Self::Guard;
+}
+
+pub trait ReclaimGuard {
+    // loads the pointer and keeps what it points to from being freed while the guard lives,
+    // or until the index is used to protect another pointer
+    fn protect<T>(&mut self, index: usize, source: &AtomicPtr<T>) -> *mut T;
+
+    // the pointer must come from Box::into_raw and be unreachable for operations that start later
+    unsafe fn retire<T>(&self, ptr: *mut T);
+}
+
+#[cfg(test)]
+mod stress {
+    use std::sync::{Arc, Barrier};
+    use std::sync::atomic::{AtomicIsize, Ordering};
+    use std::thread;
+
+    use super::ConcurrentQueue;
+
+    const ITEMS_PER_WRITER: usize = 32768;
+
+    pub fn readers_writers() -> Vec<(usize, usize)> {
+        (0..4).map(|i| (1 << i, 1 << i)).collect()
+    }
+
+    pub fn publish_subscribe<Q>(queue: Q, readers: usize, writers: usize)
+        where Q: ConcurrentQueue<usize> + Send + Sync + 'static {
+        let queue = Arc::new(queue);
+        let start = Arc::new(Barrier::new(readers + writers));
+        let last = Arc::new(AtomicIsize::new((writers * ITEMS_PER_WRITER) as isize));
+
+        let writer_handles = (0..writers).map(|writer| {
+            let queue = queue.clone();
+            let start = start.clone();
+            thread::spawn(move || {
+                start.wait();
+                for item in writer * ITEMS_PER_WRITER..(writer + 1) * ITEMS_PER_WRITER {
+                    queue.enqueue(item);
+                }
+            })
+        }).collect::<Vec<_>>();
+
+        let reader_handles = (0..readers).map(|_| {
+            let queue = queue.clone();
+            let start = start.clone();
+            let last = last.clone();
+            thread::spawn(move || {
+                let mut items = vec![];
+                start.wait();
+                while last.fetch_sub(1, Ordering::SeqCst) > 0 {
+                    loop {
+                        match queue.deque() {
+                            Some(item) => {
+                                items.push(item);
+                                break;
+                            }
+                            None => thread::yield_now()
+                        }
+                    }
+                }
+                items
+            })
+        }).collect::<Vec<_>>();
+
+        for handle in writer_handles {
+            handle.join().unwrap();
+        }
+        let mut items = reader_handles.into_iter()
+            .flat_map(|handle| handle.join().unwrap().into_iter())
+            .collect::<Vec<usize>>();
+        items.sort();
+
+        assert_eq!(items, (0..writers * ITEMS_PER_WRITER).collect::<Vec<usize>>());
+        assert_eq!(queue.deque(), None);
+    }
+}
@@ -0,0 +1,512 @@
+extern crate alloc;
+
+use self::alloc::raw_vec::RawVec;
+
+use std::cell::UnsafeCell;
+use std::cmp;
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::thread;
+
+use super::{ConcurrentQueue, Reclaimer, ReclaimGuard};
+use super::epoch::Epoch;
+use super::padding::{CacheLine, CachePadded, NoPadding, Padding};
+use super::super::Full;
+
+// a slot is free for the enqueuer at position p when its sequence is p
+// and holds an item for the dequer at position p when its sequence is p + 1
+struct Slot<T> {
+    sequence: AtomicUsize,
+    item: UnsafeCell<T>
+}
+
+pub struct MpmcArrayQueue<T, P: Padding = NoPadding> {
+    head: CachePadded<AtomicUsize, P>,
+    tail: CachePadded<AtomicUsize, P>,
+    mask: usize,
+    slots: RawVec<Slot<T>>
+}
+
+unsafe impl <T: Send, P: Padding> Send for MpmcArrayQueue<T, P> {}
+unsafe impl <T: Send, P: Padding> Sync for MpmcArrayQueue<T, P> {}
+
+pub type PaddedMpmcArrayQueue<T> = MpmcArrayQueue<T, CacheLine>;
+
+impl <T> MpmcArrayQueue<T> {
+    pub fn new(capacity: usize) -> MpmcArrayQueue<T> {
+        MpmcArrayQueue::with_padding(capacity, NoPadding)
+    }
+}
+
+impl <T, P: Padding> MpmcArrayQueue<T, P> {
+    // a single slot cannot tell a full queue from an empty one, so there are at least two
+    pub fn with_padding(capacity: usize, _padding: P) -> MpmcArrayQueue<T, P> {
+        let capacity = cmp::max(capacity, 2).next_power_of_two();
+        let slots: RawVec<Slot<T>> = RawVec::with_capacity(capacity);
+        for index in 0..capacity {
+            unsafe {
+                let slot = slots.ptr().offset(index as isize);
+                ptr::write(&mut (*slot).sequence, AtomicUsize::new(index));
+            }
+        }
+        MpmcArrayQueue {
+            head: CachePadded::new(AtomicUsize::new(0)),
+            tail: CachePadded::new(AtomicUsize::new(0)),
+            mask: capacity - 1,
+            slots: slots
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    // only a snapshot while other threads keep enqueueing and dequeing
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::SeqCst);
+        let tail = self.tail.load(Ordering::SeqCst);
+        cmp::min(tail.wrapping_sub(head), self.capacity())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // spins until a slot is free
+    pub fn enqueue(&self, item: T) {
+        let mut item = item;
+        while let Err(Full(rejected)) = self.try_enqueue(item) {
+            item = rejected;
+            thread::yield_now();
+        }
+    }
+
+    pub fn try_enqueue(&self, item: T) -> Result<(), Full<T>> {
+        let mut position = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(position);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let lag = sequence.wrapping_sub(position) as isize;
+            if lag == 0 {
+                match self.tail.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        unsafe { ptr::write(slot.item.get(), item); }
+                        slot.sequence.store(position.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => position = current
+                }
+            } else if lag < 0 {
+                return Err(Full(item));
+            } else {
+                position = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn deque(&self) -> Option<T> {
+        let mut position = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(position);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let lag = sequence.wrapping_sub(position.wrapping_add(1)) as isize;
+            if lag == 0 {
+                match self.head.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        let item = unsafe { ptr::read(slot.item.get()) };
+                        slot.sequence.store(position.wrapping_add(self.capacity()), Ordering::Release);
+                        return Some(item);
+                    }
+                    Err(current) => position = current
+                }
+            } else if lag < 0 {
+                return None;
+            } else {
+                position = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn slot(&self, position: usize) -> &Slot<T> {
+        unsafe { &*self.slots.ptr().offset((position & self.mask) as isize) }
+    }
+}
+
+impl <T, P: Padding> ConcurrentQueue<T> for MpmcArrayQueue<T, P> {
+    fn enqueue(&self, item: T) {
+        MpmcArrayQueue::enqueue(self, item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        MpmcArrayQueue::deque(self)
+    }
+}
+
+impl <T, P: Padding> Drop for MpmcArrayQueue<T, P> {
+    fn drop(&mut self) {
+        while let Some(_) = self.deque() {}
+    }
+}
+
+struct SegmentSlot<T> {
+    ready: AtomicBool,
+    item: UnsafeCell<Option<T>>
+}
+
+struct Segment<T> {
+    slots: Box<[SegmentSlot<T>]>,
+    // claimed by producers, goes past the capacity once the segment is full
+    enqueued: AtomicUsize,
+    dequed: AtomicUsize,
+    next: AtomicPtr<Segment<T>>
+}
+
+impl <T> Segment<T> {
+    fn new(capacity: usize) -> *mut Segment<T> {
+        let slots = (0..capacity)
+            .map(|_| SegmentSlot { ready: AtomicBool::new(false), item: UnsafeCell::new(None) })
+            .collect::<Vec<SegmentSlot<T>>>();
+        Box::into_raw(Box::new(Segment {
+            slots: slots.into_boxed_slice(),
+            enqueued: AtomicUsize::new(0),
+            dequed: AtomicUsize::new(0),
+            next: AtomicPtr::new(ptr::null_mut())
+        }))
+    }
+}
+
+// producers claim slots of the tail segment with a fetch-add, consumers claim slots of the
+// head segment with a compare-and-swap so that they never run ahead of the producers,
+// exhausted segments are retired to the reclaimer
+//
+// not lock-free: like crossbeam's SegQueue it blocks at slot granularity, a consumer that claimed
+// a slot waits for its producer to write it, so a producer preempted between the fetch-add
+// and the write stalls the consumer that claimed its slot until the producer runs again
+pub struct SegmentedMpmcQueue<T, P: Padding = NoPadding, R: Reclaimer = Epoch> {
+    head: CachePadded<AtomicPtr<Segment<T>>, P>,
+    tail: CachePadded<AtomicPtr<Segment<T>>, P>,
+    segment_capacity: usize,
+    _reclaimer: PhantomData<R>
+}
+
+unsafe impl <T: Send, P: Padding, R: Reclaimer> Send for SegmentedMpmcQueue<T, P, R> {}
+unsafe impl <T: Send, P: Padding, R: Reclaimer> Sync for SegmentedMpmcQueue<T, P, R> {}
+
+pub type PaddedSegmentedMpmcQueue<T> = SegmentedMpmcQueue<T, CacheLine>;
+
+impl <T> SegmentedMpmcQueue<T> {
+    pub fn new(segment_capacity: usize) -> SegmentedMpmcQueue<T> {
+        SegmentedMpmcQueue::with_reclaimer(segment_capacity, NoPadding, Epoch)
+    }
+}
+
+impl <T, P: Padding> SegmentedMpmcQueue<T, P> {
+    pub fn with_padding(segment_capacity: usize, padding: P) -> SegmentedMpmcQueue<T, P> {
+        SegmentedMpmcQueue::with_reclaimer(segment_capacity, padding, Epoch)
+    }
+}
+
+impl <T, P: Padding, R: Reclaimer> SegmentedMpmcQueue<T, P, R> {
+    pub fn with_reclaimer(segment_capacity: usize, _padding: P, _reclaimer: R) -> SegmentedMpmcQueue<T, P, R> {
+        assert!(segment_capacity > 0, "segment capacity must be positive");
+        let segment = Segment::new(segment_capacity);
+        SegmentedMpmcQueue {
+            head: CachePadded::new(AtomicPtr::new(segment)),
+            tail: CachePadded::new(AtomicPtr::new(segment)),
+            segment_capacity: segment_capacity,
+            _reclaimer: PhantomData
+        }
+    }
+
+    pub fn segment_capacity(&self) -> usize {
+        self.segment_capacity
+    }
+
+    pub fn enqueue(&self, item: T) {
+        let mut guard = R::pin();
+        loop {
+            let tail = guard.protect(0, &self.tail);
+            let segment = unsafe { &*tail };
+            let index = segment.enqueued.fetch_add(1, Ordering::SeqCst);
+            if index < self.segment_capacity {
+                let slot = &segment.slots[index];
+                unsafe { *slot.item.get() = Some(item); }
+                slot.ready.store(true, Ordering::Release);
+                return;
+            }
+            let next = segment.next.load(Ordering::SeqCst);
+            if next.is_null() {
+                let installed = Segment::new(self.segment_capacity);
+                match segment.next.compare_exchange(ptr::null_mut(), installed, Ordering::SeqCst, Ordering::SeqCst) {
+                    Ok(_) => {
+                        let _ = self.tail.compare_exchange(tail, installed, Ordering::SeqCst, Ordering::SeqCst);
+                    }
+                    Err(current) => {
+                        unsafe { drop(Box::from_raw(installed)); }
+                        let _ = self.tail.compare_exchange(tail, current, Ordering::SeqCst, Ordering::SeqCst);
+                    }
+                }
+            } else {
+                let _ = self.tail.compare_exchange(tail, next, Ordering::SeqCst, Ordering::SeqCst);
+            }
+        }
+    }
+
+    pub fn deque(&self) -> Option<T> {
+        let mut guard = R::pin();
+        loop {
+            let head = guard.protect(0, &self.head);
+            let segment = unsafe { &*head };
+            let index = segment.dequed.load(Ordering::SeqCst);
+            if index < self.segment_capacity {
+                if index >= segment.enqueued.load(Ordering::SeqCst) {
+                    return None;
+                }
+                if segment.dequed.compare_exchange_weak(index, index + 1, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                    // the producer that claimed the slot may not have written it yet
+                    let slot = &segment.slots[index];
+                    while !slot.ready.load(Ordering::Acquire) {
+                        thread::yield_now();
+                    }
+                    return unsafe { (*slot.item.get()).take() };
+                }
+            } else {
+                let next = segment.next.load(Ordering::SeqCst);
+                if next.is_null() {
+                    return None;
+                }
+                // the tail has to leave the segment before it becomes unreachable
+                let _ = self.tail.compare_exchange(head, next, Ordering::SeqCst, Ordering::SeqCst);
+                if self.head.compare_exchange(head, next, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                    unsafe { guard.retire(head); }
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let mut guard = R::pin();
+        loop {
+            let head = guard.protect(0, &self.head);
+            let segment = unsafe { &*head };
+            let enqueued = cmp::min(segment.enqueued.load(Ordering::SeqCst), self.segment_capacity);
+            if segment.dequed.load(Ordering::SeqCst) < enqueued {
+                return false;
+            }
+            if enqueued < self.segment_capacity {
+                return true;
+            }
+            let next = guard.protect(1, &segment.next);
+            if next.is_null() {
+                return true;
+            }
+            // with the head in place the next segment is reachable and nothing was dequed from it
+            if head == self.head.load(Ordering::SeqCst) {
+                return unsafe { (*next).enqueued.load(Ordering::SeqCst) == 0 };
+            }
+        }
+    }
+}
+
+impl <T, P: Padding, R: Reclaimer> ConcurrentQueue<T> for SegmentedMpmcQueue<T, P, R> {
+    fn enqueue(&self, item: T) {
+        SegmentedMpmcQueue::enqueue(self, item)
+    }
+
+    fn deque(&self) -> Option<T> {
+        SegmentedMpmcQueue::deque(self)
+    }
+}
+
+impl <T, P: Padding, R: Reclaimer> Drop for SegmentedMpmcQueue<T, P, R> {
+    fn drop(&mut self) {
+        let mut segment = self.head.load(Ordering::Relaxed);
+        while !segment.is_null() {
+            unsafe {
+                let next = (*segment).next.load(Ordering::Relaxed);
+                drop(Box::from_raw(segment));
+                segment = next;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod mpmc_array_queue {
+        use std::rc::Rc;
+
+        use super::super::MpmcArrayQueue;
+        use super::super::super::padding::CacheLine;
+        use super::super::super::stress;
+        use super::super::super::super::Full;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let queue: MpmcArrayQueue<i32> = MpmcArrayQueue::new(16);
+
+            assert_eq!(queue.deque(), None);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn enqueue_many_items() {
+            let queue = MpmcArrayQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert_eq!(queue.len(), 3);
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn capacity_is_rounded_up_to_power_of_two() {
+            assert_eq!(MpmcArrayQueue::<i32>::new(5).capacity(), 8);
+            assert_eq!(MpmcArrayQueue::<i32>::new(1).capacity(), 2);
+        }
+
+        #[test]
+        fn try_enqueue_into_full_queue() {
+            let queue = MpmcArrayQueue::new(2);
+
+            assert_eq!(queue.try_enqueue(10), Ok(()));
+            assert_eq!(queue.try_enqueue(20), Ok(()));
+            assert_eq!(queue.try_enqueue(30), Err(Full(30)));
+
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.try_enqueue(30), Ok(()));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+        }
+
+        #[test]
+        fn enqueue_and_deque_around_the_ring() {
+            let queue = MpmcArrayQueue::new(4);
+
+            for item in 0..100 {
+                queue.enqueue(item);
+                queue.enqueue(item + 1000);
+                assert_eq!(queue.deque(), Some(item));
+                assert_eq!(queue.deque(), Some(item + 1000));
+            }
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = MpmcArrayQueue::new(16);
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                stress::publish_subscribe(MpmcArrayQueue::new(64), readers, writers);
+                stress::publish_subscribe(MpmcArrayQueue::with_padding(64, CacheLine), readers, writers);
+            }
+        }
+    }
+
+    mod segmented_mpmc_queue {
+        use std::rc::Rc;
+
+        use super::super::SegmentedMpmcQueue;
+        use super::super::super::hazard::HazardPointers;
+        use super::super::super::padding::{CacheLine, NoPadding};
+        use super::super::super::stress;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let queue: SegmentedMpmcQueue<i32> = SegmentedMpmcQueue::new(4);
+
+            assert_eq!(queue.deque(), None);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn enqueue_many_items() {
+            let queue = SegmentedMpmcQueue::new(4);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert!(!queue.is_empty());
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn enqueue_deque_items_more_than_segment_capacity() {
+            let queue = SegmentedMpmcQueue::new(4);
+
+            for item in 0..10 {
+                queue.enqueue(item);
+            }
+
+            for item in 0..10 {
+                assert!(!queue.is_empty());
+                assert_eq!(queue.deque(), Some(item));
+            }
+            assert!(queue.is_empty());
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn queue_is_not_empty_when_items_wait_in_next_segment() {
+            let queue = SegmentedMpmcQueue::new(2);
+
+            for item in 0..3 {
+                queue.enqueue(item);
+            }
+            queue.deque();
+            queue.deque();
+
+            assert!(!queue.is_empty());
+            assert_eq!(queue.deque(), Some(2));
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let queue = SegmentedMpmcQueue::new(4);
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn publish_subscribe() {
+            for (readers, writers) in stress::readers_writers() {
+                stress::publish_subscribe(SegmentedMpmcQueue::new(4), readers, writers);
+                stress::publish_subscribe(SegmentedMpmcQueue::new(256), readers, writers);
+                stress::publish_subscribe(SegmentedMpmcQueue::with_padding(256, CacheLine), readers, writers);
+                stress::publish_subscribe(SegmentedMpmcQueue::with_reclaimer(4, NoPadding, HazardPointers), readers, writers);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+// selects whether the hot fields of a concurrent queue are kept on separate cache lines
+pub trait Padding {
+    type Alignment: Default;
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NoPadding;
+
+impl Padding for NoPadding {
+    type Alignment = ();
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLine;
+
+impl Padding for CacheLine {
+    type Alignment = CacheLineAlignment;
+}
+
+// two cache lines, as the java padding does, so that adjacent line prefetch does not
+// bring false sharing back
+#[repr(align(128))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheLineAlignment;
+
+pub struct CachePadded<T, P: Padding = CacheLine> {
+    value: T,
+    _alignment: P::Alignment
+}
+
+impl <T, P: Padding> CachePadded<T, P> {
+    pub fn new(value: T) -> CachePadded<T, P> {
+        CachePadded {
+            value: value,
+            _alignment: Default::default()
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl <T, P: Padding> Deref for CachePadded<T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl <T, P: Padding> DerefMut for CachePadded<T, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl <T: Default, P: Padding> Default for CachePadded<T, P> {
+    fn default() -> CachePadded<T, P> {
+        CachePadded::new(T::default())
+    }
+}
+
+impl <T: fmt::Debug, P: Padding> fmt::Debug for CachePadded<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CachePadded").field(&self.value).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod cache_padded {
+        use std::mem;
+        use std::sync::atomic::AtomicUsize;
+
+        use super::super::{CacheLine, CachePadded, NoPadding};
+
+        #[test]
+        fn padded_value_takes_whole_cache_lines() {
+            assert_eq!(mem::align_of::<CachePadded<u8>>(), 128);
+            assert_eq!(mem::size_of::<CachePadded<u8>>(), 128);
+            assert_eq!(mem::size_of::<CachePadded<[u8; 130]>>(), 256);
+        }
+
+        #[test]
+        fn no_padding_keeps_value_layout() {
+            assert_eq!(mem::size_of::<CachePadded<u8, NoPadding>>(), 1);
+            assert_eq!(mem::size_of::<CachePadded<AtomicUsize, NoPadding>>(), mem::size_of::<AtomicUsize>());
+        }
+
+        #[test]
+        fn neighbours_are_on_different_cache_lines() {
+            let pair: [CachePadded<u8, CacheLine>; 2] = [CachePadded::new(1), CachePadded::new(2)];
+
+            let first = &*pair[0] as *const u8 as usize;
+            let second = &*pair[1] as *const u8 as usize;
+
+            assert_eq!(first % 128, 0);
+            assert_eq!(second - first, 128);
+        }
+
+        #[test]
+        fn deref_to_value() {
+            let mut padded: CachePadded<i32> = CachePadded::new(10);
+
+            *padded += 10;
+
+            assert_eq!(*padded, 20);
+            assert_eq!(padded.into_inner(), 20);
+        }
+    }
+}
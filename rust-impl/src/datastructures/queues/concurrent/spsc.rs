@@ -0,0 +1,317 @@
+extern crate alloc;
+
+use self::alloc::raw_vec::RawVec;
+
+use std::cmp;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::padding::CachePadded;
+use super::super::Full;
+use super::super::sequential::array::{copy_from_ring, copy_into_ring};
+
+// head and tail only ever grow, a slot is the index masked by the capacity
+struct Ring<T> {
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+    mask: usize,
+    data: RawVec<T>
+}
+
+unsafe impl <T: Send> Send for Ring<T> {}
+unsafe impl <T: Send> Sync for Ring<T> {}
+
+impl <T> Ring<T> {
+    fn slot(&self, index: usize) -> *mut T {
+        unsafe { self.data.ptr().offset((index & self.mask) as isize) }
+    }
+}
+
+impl <T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let mut head = self.head.load(Ordering::Relaxed);
+        while head != tail {
+            unsafe { ptr::drop_in_place(self.slot(head)); }
+            head = head.wrapping_add(1);
+        }
+    }
+}
+
+pub fn channel<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let capacity = capacity.next_power_of_two();
+    let ring = Arc::new(Ring {
+        head: CachePadded::new(AtomicUsize::new(0)),
+        tail: CachePadded::new(AtomicUsize::new(0)),
+        mask: capacity - 1,
+        data: RawVec::with_capacity(capacity)
+    });
+    let producer = Producer {
+        ring: ring.clone(),
+        tail: 0,
+        cached_head: 0
+    };
+    let consumer = Consumer {
+        ring: ring,
+        head: 0,
+        cached_tail: 0
+    };
+    (producer, consumer)
+}
+
+// the producer owns the tail and looks at the head of the consumer
+// only when its cached copy says there is not enough room
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+    tail: usize,
+    cached_head: usize
+}
+
+impl <T> Producer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.mask + 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.tail.wrapping_sub(self.ring.head.load(Ordering::Acquire))
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    pub fn push(&mut self, item: T) -> Result<(), Full<T>> {
+        if self.free_slots(1) == 0 {
+            return Err(Full(item));
+        }
+        unsafe { ptr::write(self.ring.slot(self.tail), item); }
+        self.tail = self.tail.wrapping_add(1);
+        self.ring.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    // pushes as many items as fit, returns how many were pushed
+    pub fn push_slice(&mut self, items: &[T]) -> usize where T: Copy {
+        let count = cmp::min(items.len(), self.free_slots(items.len()));
+        if count > 0 {
+            unsafe {
+                copy_into_ring(&self.ring.data, self.tail & self.ring.mask, items.as_ptr(), count);
+            }
+            self.tail = self.tail.wrapping_add(count);
+            self.ring.tail.store(self.tail, Ordering::Release);
+        }
+        count
+    }
+
+    fn free_slots(&mut self, wanted: usize) -> usize {
+        let capacity = self.capacity();
+        if capacity - self.tail.wrapping_sub(self.cached_head) < wanted {
+            self.cached_head = self.ring.head.load(Ordering::Acquire);
+        }
+        capacity - self.tail.wrapping_sub(self.cached_head)
+    }
+}
+
+// the consumer owns the head and looks at the tail of the producer
+// only when its cached copy says there are not enough items
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+    head: usize,
+    cached_tail: usize
+}
+
+impl <T> Consumer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.mask + 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.tail.load(Ordering::Acquire).wrapping_sub(self.head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.ready_slots(1) == 0 {
+            return None;
+        }
+        let item = unsafe { ptr::read(self.ring.slot(self.head)) };
+        self.head = self.head.wrapping_add(1);
+        self.ring.head.store(self.head, Ordering::Release);
+        Some(item)
+    }
+
+    // pops as many items as the slice holds, returns how many were popped
+    pub fn pop_slice(&mut self, items: &mut [T]) -> usize where T: Copy {
+        let count = cmp::min(items.len(), self.ready_slots(items.len()));
+        if count > 0 {
+            unsafe {
+                copy_from_ring(&self.ring.data, self.head & self.ring.mask, items.as_mut_ptr(), count);
+            }
+            self.head = self.head.wrapping_add(count);
+            self.ring.head.store(self.head, Ordering::Release);
+        }
+        count
+    }
+
+    fn ready_slots(&mut self, wanted: usize) -> usize {
+        if self.cached_tail.wrapping_sub(self.head) < wanted {
+            self.cached_tail = self.ring.tail.load(Ordering::Acquire);
+        }
+        self.cached_tail.wrapping_sub(self.head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod channel {
+        use std::cmp;
+        use std::rc::Rc;
+        use std::thread;
+
+        use super::super::channel;
+        use super::super::super::super::Full;
+
+        #[test]
+        fn pop_from_empty_channel() {
+            let (_, mut consumer) = channel::<i32>(16);
+
+            assert_eq!(consumer.pop(), None);
+            assert!(consumer.is_empty());
+        }
+
+        #[test]
+        fn push_many_items() {
+            let (mut producer, mut consumer) = channel(16);
+
+            assert_eq!(producer.push(10), Ok(()));
+            assert_eq!(producer.push(20), Ok(()));
+            assert_eq!(producer.push(30), Ok(()));
+
+            assert_eq!(consumer.len(), 3);
+            assert_eq!(consumer.pop(), Some(10));
+            assert_eq!(consumer.pop(), Some(20));
+            assert_eq!(consumer.pop(), Some(30));
+            assert_eq!(consumer.pop(), None);
+        }
+
+        #[test]
+        fn push_into_full_channel() {
+            let (mut producer, mut consumer) = channel(3);
+
+            for item in 0..4 {
+                assert_eq!(producer.push(item), Ok(()));
+            }
+
+            assert_eq!(producer.capacity(), 4);
+            assert!(producer.is_full());
+            assert_eq!(producer.push(4), Err(Full(4)));
+            assert_eq!(consumer.pop(), Some(0));
+            assert_eq!(producer.push(4), Ok(()));
+        }
+
+        #[test]
+        fn push_and_pop_around_the_ring() {
+            let (mut producer, mut consumer) = channel(4);
+
+            for item in 0..100 {
+                assert_eq!(producer.push(item), Ok(()));
+                assert_eq!(producer.push(item + 1000), Ok(()));
+                assert_eq!(consumer.pop(), Some(item));
+                assert_eq!(consumer.pop(), Some(item + 1000));
+            }
+        }
+
+        #[test]
+        fn push_slice_fills_free_slots() {
+            let (mut producer, mut consumer) = channel(8);
+            producer.push(0).unwrap();
+            producer.push(0).unwrap();
+            consumer.pop();
+            consumer.pop();
+
+            assert_eq!(producer.push_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]), 8);
+            assert_eq!(producer.push_slice(&[11]), 0);
+
+            let mut items = [0; 5];
+            assert_eq!(consumer.pop_slice(&mut items), 5);
+            assert_eq!(items, [1, 2, 3, 4, 5]);
+            assert_eq!(consumer.pop_slice(&mut items), 3);
+            assert_eq!(&items[..3], &[6, 7, 8]);
+            assert_eq!(consumer.pop_slice(&mut items), 0);
+        }
+
+        #[test]
+        fn drop_channel_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let (mut producer, mut consumer) = channel(16);
+
+                for _ in 0..10 {
+                    producer.push(item.clone()).unwrap();
+                }
+
+                consumer.pop();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn hand_off_items_between_threads() {
+            const ITEMS: usize = 1 << 20;
+            let (mut producer, mut consumer) = channel(1024);
+
+            let writer = thread::spawn(move || {
+                for item in 0..ITEMS {
+                    let mut item = item;
+                    while let Err(Full(rejected)) = producer.push(item) {
+                        item = rejected;
+                        thread::yield_now();
+                    }
+                }
+            });
+
+            let mut expected = 0;
+            while expected < ITEMS {
+                match consumer.pop() {
+                    Some(item) => {
+                        assert_eq!(item, expected);
+                        expected += 1;
+                    }
+                    None => thread::yield_now()
+                }
+            }
+            writer.join().unwrap();
+        }
+
+        #[test]
+        fn hand_off_slices_between_threads() {
+            const ITEMS: usize = 1 << 20;
+            let (mut producer, mut consumer) = channel(1024);
+
+            let writer = thread::spawn(move || {
+                let items = (0..ITEMS).collect::<Vec<usize>>();
+                let mut pushed = 0;
+                while pushed < ITEMS {
+                    let end = cmp::min(pushed + 100, ITEMS);
+                    pushed += producer.push_slice(&items[pushed..end]);
+                }
+            });
+
+            let mut items = [0; 64];
+            let mut expected = 0;
+            while expected < ITEMS {
+                let count = consumer.pop_slice(&mut items);
+                for &item in &items[..count] {
+                    assert_eq!(item, expected);
+                    expected += 1;
+                }
+            }
+            writer.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,332 @@
+extern crate alloc;
+
+use self::alloc::raw_vec::RawVec;
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{self, AtomicIsize, AtomicPtr, Ordering};
+
+use super::{Reclaimer, ReclaimGuard};
+use super::epoch::Epoch;
+use super::padding::CachePadded;
+
+// items are addressed by ever growing indexes masked by the capacity
+struct Buffer<T> {
+    data: RawVec<T>,
+    mask: usize
+}
+
+impl <T> Buffer<T> {
+    fn new(capacity: usize) -> *mut Buffer<T> {
+        let capacity = capacity.next_power_of_two();
+        Box::into_raw(Box::new(Buffer {
+            data: RawVec::with_capacity(capacity),
+            mask: capacity - 1
+        }))
+    }
+
+    fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    unsafe fn slot(&self, index: isize) -> *mut T {
+        self.data.ptr().offset((index as usize & self.mask) as isize)
+    }
+}
+
+// the owner works at the bottom, thieves race each other and the owner for the top
+struct Deque<T> {
+    top: CachePadded<AtomicIsize>,
+    bottom: CachePadded<AtomicIsize>,
+    buffer: AtomicPtr<Buffer<T>>
+}
+
+impl <T> Drop for Deque<T> {
+    fn drop(&mut self) {
+        let top = self.top.load(Ordering::Relaxed);
+        let bottom = self.bottom.load(Ordering::Relaxed);
+        unsafe {
+            let buffer = Box::from_raw(self.buffer.load(Ordering::Relaxed));
+            for index in top..bottom {
+                ptr::drop_in_place(buffer.slot(index));
+            }
+        }
+    }
+}
+
+pub struct Worker<T, R: Reclaimer = Epoch> {
+    deque: Arc<Deque<T>>,
+    _marker: PhantomData<(Cell<()>, R)>
+}
+
+unsafe impl <T: Send, R: Reclaimer> Send for Worker<T, R> {}
+
+impl <T> Worker<T> {
+    pub fn new(capacity: usize) -> Worker<T> {
+        Worker::with_reclaimer(capacity, Epoch)
+    }
+}
+
+impl <T, R: Reclaimer> Worker<T, R> {
+    pub fn with_reclaimer(capacity: usize, _reclaimer: R) -> Worker<T, R> {
+        Worker {
+            deque: Arc::new(Deque {
+                top: CachePadded::new(AtomicIsize::new(0)),
+                bottom: CachePadded::new(AtomicIsize::new(0)),
+                buffer: AtomicPtr::new(Buffer::new(capacity))
+            }),
+            _marker: PhantomData
+        }
+    }
+
+    pub fn stealer(&self) -> Stealer<T, R> {
+        Stealer {
+            deque: self.deque.clone(),
+            _marker: PhantomData
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        unsafe { (*self.deque.buffer.load(Ordering::Relaxed)).capacity() }
+    }
+
+    pub fn len(&self) -> usize {
+        let bottom = self.deque.bottom.load(Ordering::Relaxed);
+        let top = self.deque.top.load(Ordering::SeqCst);
+        if bottom > top { (bottom - top) as usize } else { 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&self, item: T) {
+        let bottom = self.deque.bottom.load(Ordering::Relaxed);
+        let top = self.deque.top.load(Ordering::Acquire);
+        let mut buffer = self.deque.buffer.load(Ordering::Relaxed);
+        unsafe {
+            if bottom - top >= (*buffer).capacity() as isize {
+                buffer = self.resize(buffer, top, bottom, (*buffer).capacity() * 2);
+            }
+            ptr::write((*buffer).slot(bottom), item);
+        }
+        atomic::fence(Ordering::Release);
+        self.deque.bottom.store(bottom + 1, Ordering::Relaxed);
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let bottom = self.deque.bottom.load(Ordering::Relaxed) - 1;
+        let buffer = self.deque.buffer.load(Ordering::Relaxed);
+        self.deque.bottom.store(bottom, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        let top = self.deque.top.load(Ordering::Relaxed);
+        if top > bottom {
+            self.deque.bottom.store(bottom + 1, Ordering::Relaxed);
+            return None;
+        }
+        let item = unsafe { ptr::read((*buffer).slot(bottom)) };
+        if top == bottom {
+            // the last item goes to whoever moves the top first
+            let won = self.deque.top.compare_exchange(top, top + 1, Ordering::SeqCst, Ordering::Relaxed).is_ok();
+            self.deque.bottom.store(bottom + 1, Ordering::Relaxed);
+            if !won {
+                mem::forget(item);
+                return None;
+            }
+        }
+        Some(item)
+    }
+
+    // copies items into a bigger buffer the way ResizableArrayQueue::resize does, thieves that
+    // still read the old one are fine, its copies are only owned by whoever moves the top
+    unsafe fn resize(&self, old: *mut Buffer<T>, top: isize, bottom: isize, new_capacity: usize) -> *mut Buffer<T> {
+        let new = Buffer::new(new_capacity);
+        for index in top..bottom {
+            ptr::write((*new).slot(index), ptr::read((*old).slot(index)));
+        }
+        self.deque.buffer.store(new, Ordering::Release);
+        R::pin().retire(old);
+        new
+    }
+}
+
+pub struct Stealer<T, R: Reclaimer = Epoch> {
+    deque: Arc<Deque<T>>,
+    _marker: PhantomData<R>
+}
+
+unsafe impl <T: Send, R: Reclaimer> Send for Stealer<T, R> {}
+unsafe impl <T: Send, R: Reclaimer> Sync for Stealer<T, R> {}
+
+impl <T, R: Reclaimer> Stealer<T, R> {
+    pub fn is_empty(&self) -> bool {
+        let top = self.deque.top.load(Ordering::Acquire);
+        atomic::fence(Ordering::SeqCst);
+        let bottom = self.deque.bottom.load(Ordering::Acquire);
+        top >= bottom
+    }
+
+    // retries when it loses the top to another thief or to the owner
+    pub fn steal(&self) -> Option<T> {
+        let mut guard = R::pin();
+        loop {
+            let top = self.deque.top.load(Ordering::Acquire);
+            atomic::fence(Ordering::SeqCst);
+            let bottom = self.deque.bottom.load(Ordering::Acquire);
+            if top >= bottom {
+                return None;
+            }
+            let buffer = guard.protect(0, &self.deque.buffer);
+            // with a stale top the owner may be writing the wrapped slot right now, so the read is
+            // volatile and may be torn, it only becomes an item once the compare-and-swap proves
+            // that the top did not move and is forgotten untouched otherwise
+            let item = unsafe { ManuallyDrop::new(ptr::read_volatile((*buffer).slot(top))) };
+            if self.deque.top.compare_exchange(top, top + 1, Ordering::SeqCst, Ordering::Relaxed).is_ok() {
+                return Some(ManuallyDrop::into_inner(item));
+            }
+        }
+    }
+}
+
+impl <T, R: Reclaimer> Clone for Stealer<T, R> {
+    fn clone(&self) -> Stealer<T, R> {
+        Stealer {
+            deque: self.deque.clone(),
+            _marker: PhantomData
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod work_stealing_deque {
+        use std::rc::Rc;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::thread;
+
+        use super::super::Worker;
+        use super::super::super::Reclaimer;
+        use super::super::super::hazard::HazardPointers;
+
+        #[test]
+        fn pop_from_empty_deque() {
+            let worker: Worker<i32> = Worker::new(16);
+
+            assert_eq!(worker.pop(), None);
+            assert_eq!(worker.stealer().steal(), None);
+            assert!(worker.is_empty());
+        }
+
+        #[test]
+        fn owner_pops_last_pushed_item() {
+            let worker = Worker::new(16);
+
+            worker.push(10);
+            worker.push(20);
+            worker.push(30);
+
+            assert_eq!(worker.len(), 3);
+            assert_eq!(worker.pop(), Some(30));
+            assert_eq!(worker.pop(), Some(20));
+            assert_eq!(worker.pop(), Some(10));
+            assert_eq!(worker.pop(), None);
+        }
+
+        #[test]
+        fn thief_steals_first_pushed_item() {
+            let worker = Worker::new(16);
+            let stealer = worker.stealer();
+
+            worker.push(10);
+            worker.push(20);
+            worker.push(30);
+
+            assert_eq!(stealer.steal(), Some(10));
+            assert_eq!(worker.pop(), Some(30));
+            assert_eq!(stealer.steal(), Some(20));
+            assert!(stealer.is_empty());
+        }
+
+        #[test]
+        fn buffer_grows_when_full() {
+            let worker = Worker::new(4);
+            let stealer = worker.stealer();
+
+            for item in 0..10 {
+                worker.push(item);
+            }
+
+            assert_eq!(worker.capacity(), 16);
+            assert_eq!(stealer.steal(), Some(0));
+            for item in (1..10).rev() {
+                assert_eq!(worker.pop(), Some(item));
+            }
+        }
+
+        #[test]
+        fn drop_deque_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let worker = Worker::new(4);
+
+                for _ in 0..10 {
+                    worker.push(item.clone());
+                }
+
+                worker.pop();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        fn owner_and_thieves<R: Reclaimer + 'static>(worker: Worker<usize, R>, items: usize, thieves: usize) {
+            let done = Arc::new(AtomicBool::new(false));
+            let handles = (0..thieves).map(|_| {
+                let stealer = worker.stealer();
+                let done = done.clone();
+                thread::spawn(move || {
+                    let mut stolen = vec![];
+                    loop {
+                        let finished = done.load(Ordering::SeqCst);
+                        match stealer.steal() {
+                            Some(item) => stolen.push(item),
+                            None if finished => break,
+                            None => thread::yield_now()
+                        }
+                    }
+                    stolen
+                })
+            }).collect::<Vec<_>>();
+
+            let mut taken = vec![];
+            for item in 0..items {
+                worker.push(item);
+                if item % 3 == 0 {
+                    taken.extend(worker.pop());
+                }
+            }
+            while let Some(item) = worker.pop() {
+                taken.push(item);
+            }
+            done.store(true, Ordering::SeqCst);
+
+            for handle in handles {
+                taken.extend(handle.join().unwrap());
+            }
+            taken.sort();
+            assert_eq!(taken, (0..items).collect::<Vec<usize>>());
+        }
+
+        #[test]
+        fn owner_races_thieves() {
+            for thieves in 1..5 {
+                owner_and_thieves(Worker::new(2), 1 << 15, thieves);
+                owner_and_thieves(Worker::with_reclaimer(2, HazardPointers), 1 << 15, thieves);
+            }
+        }
+    }
+}
@@ -1,7 +1,147 @@
+pub mod concurrent;
 pub mod sequential;
 
+use std::marker::PhantomData;
+use std::ops::Range;
+
 pub trait Queue<E> {
     fn enqueue(&mut self, item: E);
 
     fn deque(&mut self) -> Option<E>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn peek(&self) -> Option<&E>;
+
+    fn peek_mut(&mut self) -> Option<&mut E>;
+
+    // rotates every item outside the range through deque and enqueue, so it costs O(len) whatever
+    // the range is, and a leaked Drain leaves the queue rotated, the array queues drain in place instead
+    fn drain(&mut self, range: Range<usize>) -> Drain<E, Self> where Self: Sized {
+        Drain::new(self, range)
+    }
+
+    fn enqueue_all<I: IntoIterator<Item = E>>(&mut self, items: I) where Self: Sized {
+        for item in items {
+            self.enqueue(item);
+        }
+    }
+
+    fn enqueue_slice(&mut self, items: &[E]) where E: Copy {
+        for &item in items {
+            self.enqueue(item);
+        }
+    }
+
+    fn deque_into(&mut self, items: &mut Vec<E>, n: usize) -> usize {
+        let mut moved = 0;
+        while moved < n {
+            match self.deque() {
+                Some(item) => items.push(item),
+                None => break
+            }
+            moved += 1;
+        }
+        moved
+    }
+}
+
+pub trait BoundedQueue<E>: Queue<E> {
+    fn capacity(&self) -> usize;
+
+    fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    fn try_enqueue(&mut self, item: E) -> Result<(), Full<E>>;
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Full<E>(pub E);
+
+impl <E> Full<E> {
+    pub fn into_inner(self) -> E {
+        self.0
+    }
+}
+
+pub struct IntoIter<E, Q: Queue<E>> {
+    queue: Q,
+    _marker: PhantomData<E>
+}
+
+impl <E, Q: Queue<E>> IntoIter<E, Q> {
+    pub fn new(queue: Q) -> IntoIter<E, Q> {
+        IntoIter {
+            queue: queue,
+            _marker: PhantomData
+        }
+    }
+}
+
+impl <E, Q: Queue<E>> Iterator for IntoIter<E, Q> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        self.queue.deque()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len(), Some(self.queue.len()))
+    }
+}
+
+// items before the range are rotated to the back up front, items after it are rotated
+// when the drain is dropped, so the queue keeps its order using only enqueue and deque
+pub struct Drain<'a, E: 'a, Q: 'a + Queue<E>> {
+    queue: &'a mut Q,
+    remaining: usize,
+    after: usize,
+    _marker: PhantomData<E>
+}
+
+impl <'a, E, Q: Queue<E>> Drain<'a, E, Q> {
+    fn new(queue: &'a mut Q, range: Range<usize>) -> Drain<'a, E, Q> {
+        let len = queue.len();
+        assert!(range.start <= range.end && range.end <= len, "drain range {:?} is out of queue bounds 0..{}", range, len);
+        for _ in 0..range.start {
+            queue.deque().map(|item| queue.enqueue(item));
+        }
+        Drain {
+            queue: queue,
+            remaining: range.end - range.start,
+            after: len - range.end,
+            _marker: PhantomData
+        }
+    }
+}
+
+impl <'a, E, Q: Queue<E>> Iterator for Drain<'a, E, Q> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        if self.remaining == 0 {
+            None
+        } else {
+            self.remaining -= 1;
+            self.queue.deque()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl <'a, E, Q: Queue<E>> Drop for Drain<'a, E, Q> {
+    fn drop(&mut self) {
+        while let Some(_) = self.next() {}
+        for _ in 0..self.after {
+            self.queue.deque().map(|item| self.queue.enqueue(item));
+        }
+    }
 }
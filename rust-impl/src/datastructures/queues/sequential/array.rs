@@ -2,53 +2,181 @@ extern crate alloc;
 
 use self::alloc::raw_vec::RawVec;
 
+use std::cmp;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::Range;
 use std::ptr;
 use std::usize;
 
-use super::Queue;
+use super::super::{BoundedQueue, Full, IntoIter, Queue};
 
 const MIN_CAPACITY: usize = 16;
 const MAX_CAPACITY: usize = usize::MAX;
 
-pub struct ResizableArrayQueue<T> {
+pub trait ResizePolicy {
+    fn grow(&self, capacity: usize) -> usize;
+
+    fn shrink(&self, size: usize, capacity: usize) -> Option<usize> {
+        if size > MIN_CAPACITY && size <= capacity / 4 {
+            Some(capacity / 2)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Doubling;
+
+impl ResizePolicy for Doubling {
+    fn grow(&self, capacity: usize) -> usize {
+        cmp::max(capacity.saturating_mul(2), 1)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OneAndHalf;
+
+impl ResizePolicy for OneAndHalf {
+    fn grow(&self, capacity: usize) -> usize {
+        cmp::max(capacity.saturating_add(capacity / 2), capacity + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FixedIncrement(pub usize);
+
+impl ResizePolicy for FixedIncrement {
+    fn grow(&self, capacity: usize) -> usize {
+        capacity.saturating_add(cmp::max(self.0, 1))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NeverShrink<P>(pub P);
+
+impl <P: ResizePolicy> ResizePolicy for NeverShrink<P> {
+    fn grow(&self, capacity: usize) -> usize {
+        self.0.grow(capacity)
+    }
+
+    fn shrink(&self, _size: usize, _capacity: usize) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HysteresisShrink<P> {
+    growth: P,
+    ratio: usize,
+    floor: usize
+}
+
+impl <P: ResizePolicy> HysteresisShrink<P> {
+    // ratio must exceed 2, otherwise halving a queue leaves it full and the next enqueue grows it back
+    pub fn new(growth: P, ratio: usize, floor: usize) -> Self {
+        assert!(ratio > 2, "shrink ratio should be greater than 2, but was {}", ratio);
+        HysteresisShrink {
+            growth: growth,
+            ratio: ratio,
+            floor: floor
+        }
+    }
+}
+
+impl <P: ResizePolicy> ResizePolicy for HysteresisShrink<P> {
+    fn grow(&self, capacity: usize) -> usize {
+        self.growth.grow(capacity)
+    }
+
+    fn shrink(&self, size: usize, capacity: usize) -> Option<usize> {
+        if capacity > self.floor && size <= capacity / self.ratio {
+            Some(cmp::max(capacity / 2, self.floor))
+        } else {
+            None
+        }
+    }
+}
+
+pub struct ResizableArrayQueue<T, P = Doubling> {
     head: usize,
     tail: usize,
     size: usize,
+    policy: P,
     data: RawVec<T>
 }
 
 impl <T> ResizableArrayQueue<T> {
     pub fn new(capacity: usize) -> Self {
+        ResizableArrayQueue::with_policy(capacity, Doubling)
+    }
+}
+
+impl <T, P: ResizePolicy> ResizableArrayQueue<T, P> {
+    pub fn with_policy(capacity: usize, policy: P) -> Self {
         ResizableArrayQueue {
             head: 0,
             tail: 0,
             size: 0,
+            policy: policy,
             data: RawVec::with_capacity(capacity),
         }
     }
 
+    pub fn capacity(&self) -> usize {
+        self.data.cap()
+    }
+
+    pub fn iter(&self) -> ArrayIter<T> {
+        ArrayIter::new(&self.data, self.head, self.size)
+    }
+
+    pub fn iter_mut(&mut self) -> ArrayIterMut<T> {
+        ArrayIterMut::new(&mut self.data, self.head, self.size)
+    }
+
+    // removes the range in place instead of rotating the rest of the queue through deque and enqueue
+    pub fn drain(&mut self, range: Range<usize>) -> ArrayDrain<T> {
+        ArrayDrain::new(&self.data, &mut self.head, &mut self.tail, &mut self.size, range)
+    }
+
     fn resize(&mut self, new_capacity: usize) {
-        let new_data = RawVec::with_capacity(new_capacity);
-        let mask = self.data.cap() - 1;
+        let new_data: RawVec<T> = RawVec::with_capacity(new_capacity);
         for i in 0..self.size {
             unsafe {
                 let to_write = new_data.ptr().offset(i as isize);
-                let to_read = self.data.ptr().offset(((self.head + i) & mask) as isize);
+                let to_read = self.data.ptr().offset(self.wrap(self.head + i) as isize);
                 let item = ptr::read(to_read);
                 ptr::write(to_write, item);
             }
         }
         self.data = new_data;
         self.head = 0;
-        self.tail = self.size;
+        self.tail = self.wrap(self.size);
+    }
+
+    fn shrink(&mut self) {
+        let capacity = self.data.cap();
+        if let Some(new_capacity) = self.policy.shrink(self.size, capacity) {
+            if new_capacity < capacity {
+                self.resize(cmp::max(new_capacity, self.size));
+            }
+        }
     }
 
-    fn is_empty(&self) -> bool {
-        self.size == 0
+    // capacity is not bound to powers of two, so indexes wrap by comparison instead of a mask
+    fn wrap(&self, index: usize) -> usize {
+        let capacity = self.data.cap();
+        if index >= capacity {
+            index - capacity
+        } else {
+            index
+        }
     }
 }
 
-impl <T> Queue<T> for ResizableArrayQueue<T> {
+impl <T, P: ResizePolicy> Queue<T> for ResizableArrayQueue<T, P> {
     fn deque(&mut self) -> Option<T> {
         if self.is_empty() {
             None
@@ -58,11 +186,8 @@ impl <T> Queue<T> for ResizableArrayQueue<T> {
                 ptr::read(to_read)
             };
             self.size -= 1;
-            self.head = (self.head + 1) & (self.data.cap() - 1);
-            let capacity = self.data.cap();
-            if self.size > MIN_CAPACITY && self.size == (capacity / 4) {
-                self.resize(capacity / 2);
-            }
+            self.head = self.wrap(self.head + 1);
+            self.shrink();
             Some(item)
         }
     }
@@ -70,36 +195,203 @@ impl <T> Queue<T> for ResizableArrayQueue<T> {
     fn enqueue(&mut self, item: T) {
         let capacity = self.data.cap();
         if self.size == capacity && capacity < MAX_CAPACITY {
-            self.resize(capacity * 2);
+            let new_capacity = self.policy.grow(capacity);
+            assert!(new_capacity > capacity, "resize policy should grow capacity {}, but returned {}", capacity, new_capacity);
+            self.resize(new_capacity);
         }
         unsafe {
             let to_write = self.data.ptr().offset(self.tail as isize);
             ptr::write(to_write, item);
         }
         self.size += 1;
-        self.tail = (self.tail + 1) & (self.data.cap() - 1);
+        self.tail = self.wrap(self.tail + 1);
+    }
+
+    fn enqueue_slice(&mut self, items: &[T]) where T: Copy {
+        let required = self.size + items.len();
+        let mut capacity = self.data.cap();
+        while capacity < required {
+            let new_capacity = self.policy.grow(capacity);
+            assert!(new_capacity > capacity, "resize policy should grow capacity {}, but returned {}", capacity, new_capacity);
+            capacity = new_capacity;
+        }
+        if capacity > self.data.cap() {
+            self.resize(capacity);
+        }
+        unsafe {
+            copy_into_ring(&self.data, self.tail, items.as_ptr(), items.len());
+        }
+        self.size = required;
+        self.tail = self.wrap(self.tail + items.len());
+    }
+
+    fn deque_into(&mut self, items: &mut Vec<T>, n: usize) -> usize {
+        let count = cmp::min(n, self.size);
+        items.reserve(count);
+        unsafe {
+            let len = items.len();
+            copy_from_ring(&self.data, self.head, items.as_mut_ptr().offset(len as isize), count);
+            items.set_len(len + count);
+        }
+        self.size -= count;
+        self.head = self.wrap(self.head + count);
+        self.shrink();
+        count
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            unsafe { Some(&*self.data.ptr().offset(self.head as isize)) }
+        }
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            unsafe { Some(&mut *self.data.ptr().offset(self.head as isize)) }
+        }
+    }
+}
+
+impl <T, P> Drop for ResizableArrayQueue<T, P> {
+    fn drop(&mut self) {
+        drop_items(&self.data, self.head, self.size);
+    }
+}
+
+impl <T> FromIterator<T> for ResizableArrayQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let items = items.into_iter();
+        let mut queue = ResizableArrayQueue::new(cmp::max(items.size_hint().0, MIN_CAPACITY));
+        queue.enqueue_all(items);
+        queue
+    }
+}
+
+impl <T, P: ResizePolicy> Extend<T> for ResizableArrayQueue<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <T, P: ResizePolicy> IntoIterator for ResizableArrayQueue<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, ResizableArrayQueue<T, P>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, T, P: ResizePolicy> IntoIterator for &'a ResizableArrayQueue<T, P> {
+    type Item = &'a T;
+    type IntoIter = ArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+impl <'a, T, P: ResizePolicy> IntoIterator for &'a mut ResizableArrayQueue<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = ArrayIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    Reject,
+    OverwriteOldest,
+    DropNewest,
+    Panic
+}
+
 pub struct NonResizableArrayQueue<T> {
     head: usize,
     tail: usize,
     size: usize,
+    policy: OverflowPolicy,
     data: RawVec<T>
 }
 
 impl <T> NonResizableArrayQueue<T> {
     pub fn new(capacity: usize) -> Self {
+        NonResizableArrayQueue::with_policy(capacity, OverflowPolicy::Panic)
+    }
+
+    pub fn with_policy(capacity: usize, policy: OverflowPolicy) -> Self {
         NonResizableArrayQueue {
             head: 0,
             tail: 0,
             size: 0,
-            data: RawVec::with_capacity(capacity),
+            policy: policy,
+            data: RawVec::with_capacity(capacity.next_power_of_two()),
+        }
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    pub fn iter(&self) -> ArrayIter<T> {
+        ArrayIter::new(&self.data, self.head, self.size)
+    }
+
+    pub fn iter_mut(&mut self) -> ArrayIterMut<T> {
+        ArrayIterMut::new(&mut self.data, self.head, self.size)
+    }
+
+    // removes the range in place instead of rotating the rest of the queue through deque and enqueue
+    pub fn drain(&mut self, range: Range<usize>) -> ArrayDrain<T> {
+        ArrayDrain::new(&self.data, &mut self.head, &mut self.tail, &mut self.size, range)
+    }
+
+    // Ok(Some(..)) carries the overwritten oldest item or the dropped new one
+    pub fn offer(&mut self, item: T) -> Result<Option<T>, Full<T>> {
+        if !self.is_full() {
+            self.write_last(item);
+            return Ok(None);
+        }
+        match self.policy {
+            OverflowPolicy::Reject => Err(Full(item)),
+            OverflowPolicy::OverwriteOldest => {
+                let oldest = self.deque();
+                self.write_last(item);
+                Ok(oldest)
+            }
+            OverflowPolicy::DropNewest => Ok(Some(item)),
+            OverflowPolicy::Panic => panic!("enqueue into full queue with capacity {}", self.capacity())
         }
     }
 
-    fn is_empty(&self) -> bool {
-        self.size == 0
+    fn grow(mut self, additional: usize) -> Self {
+        let mut grown = NonResizableArrayQueue::with_policy(cmp::max(2 * self.capacity(), self.size + additional), self.policy);
+        unsafe {
+            copy_from_ring(&self.data, self.head, grown.data.ptr(), self.size);
+        }
+        grown.size = self.size;
+        grown.tail = self.size & (grown.data.cap() - 1);
+        self.size = 0;
+        grown
+    }
+
+    fn write_last(&mut self, item: T) {
+        unsafe {
+            let to_write = self.data.ptr().offset(self.tail as isize);
+            ptr::write(to_write, item);
+        }
+        self.size += 1;
+        self.tail = (self.tail + 1) & (self.data.cap() - 1);
     }
 }
 
@@ -118,70 +410,749 @@ impl <T> Queue<T> for NonResizableArrayQueue<T> {
         }
     }
 
+    // applies the overflow policy, panics where offer would return Full
     fn enqueue(&mut self, item: T) {
+        if self.offer(item).is_err() {
+            panic!("enqueue into full queue with capacity {}", self.capacity());
+        }
+    }
+
+    // a slice that does not fit goes item by item through the overflow policy
+    fn enqueue_slice(&mut self, items: &[T]) where T: Copy {
+        if items.len() > self.capacity() - self.size {
+            for &item in items {
+                self.enqueue(item);
+            }
+        } else {
+            unsafe {
+                copy_into_ring(&self.data, self.tail, items.as_ptr(), items.len());
+            }
+            self.size += items.len();
+            self.tail = (self.tail + items.len()) & (self.data.cap() - 1);
+        }
+    }
+
+    fn deque_into(&mut self, items: &mut Vec<T>, n: usize) -> usize {
+        let count = cmp::min(n, self.size);
+        items.reserve(count);
         unsafe {
-            let to_write = self.data.ptr().offset(self.tail as isize);
-            ptr::write(to_write, item);
+            let len = items.len();
+            copy_from_ring(&self.data, self.head, items.as_mut_ptr().offset(len as isize), count);
+            items.set_len(len + count);
+        }
+        self.size -= count;
+        self.head = (self.head + count) & (self.data.cap() - 1);
+        count
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            unsafe { Some(&*self.data.ptr().offset(self.head as isize)) }
+        }
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            unsafe { Some(&mut *self.data.ptr().offset(self.head as isize)) }
+        }
+    }
+}
+
+impl <T> BoundedQueue<T> for NonResizableArrayQueue<T> {
+    fn capacity(&self) -> usize {
+        self.data.cap()
+    }
+
+    fn try_enqueue(&mut self, item: T) -> Result<(), Full<T>> {
+        if self.is_full() {
+            return Err(Full(item));
+        }
+        self.write_last(item);
+        Ok(())
+    }
+}
+
+impl <T> Drop for NonResizableArrayQueue<T> {
+    fn drop(&mut self) {
+        drop_items(&self.data, self.head, self.size);
+    }
+}
+
+// sized by the lower bound of the size hint in a single pass, an iterator yielding more than that
+// moves the collected items once per doubling of the capacity, as collecting into a Vec would
+impl <T> FromIterator<T> for NonResizableArrayQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut items = items.into_iter();
+        let mut queue = NonResizableArrayQueue::new(items.size_hint().0);
+        while let Some(item) = items.next() {
+            if queue.is_full() {
+                queue = queue.grow(items.size_hint().0 + 1);
+            }
+            queue.write_last(item);
+        }
+        queue
+    }
+}
+
+impl <T> Extend<T> for NonResizableArrayQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <T> IntoIterator for NonResizableArrayQueue<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T, NonResizableArrayQueue<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, T> IntoIterator for &'a NonResizableArrayQueue<T> {
+    type Item = &'a T;
+    type IntoIter = ArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl <'a, T> IntoIterator for &'a mut NonResizableArrayQueue<T> {
+    type Item = &'a mut T;
+    type IntoIter = ArrayIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// copies count items into the ring starting from index with at most two copies
+pub(crate) unsafe fn copy_into_ring<T>(data: &RawVec<T>, index: usize, items: *const T, count: usize) {
+    let first = cmp::min(count, data.cap() - index);
+    ptr::copy_nonoverlapping(items, data.ptr().offset(index as isize), first);
+    ptr::copy_nonoverlapping(items.offset(first as isize), data.ptr(), count - first);
+}
+
+pub(crate) unsafe fn copy_from_ring<T>(data: &RawVec<T>, index: usize, items: *mut T, count: usize) {
+    let first = cmp::min(count, data.cap() - index);
+    ptr::copy_nonoverlapping(data.ptr().offset(index as isize), items, first);
+    ptr::copy_nonoverlapping(data.ptr(), items.offset(first as isize), count - first);
+}
+
+fn drop_items<T>(data: &RawVec<T>, head: usize, size: usize) {
+    for i in 0..size {
+        unsafe {
+            ptr::drop_in_place(data.ptr().offset(((head + i) % data.cap()) as isize));
+        }
+    }
+}
+
+// the queue keeps only the items before the range until the drain is dropped, then the shorter
+// side around the range is moved to close the gap, so a leaked drain leaks the rest but nothing else
+pub struct ArrayDrain<'a, T: 'a> {
+    data: &'a RawVec<T>,
+    head: &'a mut usize,
+    tail: &'a mut usize,
+    size: &'a mut usize,
+    start: usize,
+    index: usize,
+    end: usize,
+    len: usize
+}
+
+impl <'a, T> ArrayDrain<'a, T> {
+    fn new(data: &'a RawVec<T>, head: &'a mut usize, tail: &'a mut usize, size: &'a mut usize, range: Range<usize>) -> ArrayDrain<'a, T> {
+        let len = *size;
+        assert!(range.start <= range.end && range.end <= len, "drain range {:?} is out of queue bounds 0..{}", range, len);
+        if len > 0 {
+            *tail = (*head + range.start) % data.cap();
+        }
+        *size = range.start;
+        ArrayDrain {
+            data: data,
+            head: head,
+            tail: tail,
+            size: size,
+            start: range.start,
+            index: range.start,
+            end: range.end,
+            len: len
         }
-        self.size += 1;
-        self.tail = (self.tail + 1) & (self.data.cap() - 1);
+    }
+
+    fn slot(&self, offset: usize) -> *mut T {
+        unsafe { self.data.ptr().offset(((*self.head + offset) % self.data.cap()) as isize) }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    mod non_resizable_array_queue {
-        use super::super::*;
+impl <'a, T> Iterator for ArrayDrain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.index == self.end {
+            None
+        } else {
+            let item = unsafe { ptr::read(self.slot(self.index)) };
+            self.index += 1;
+            Some(item)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.end - self.index, Some(self.end - self.index))
+    }
+}
+
+impl <'a, T> ExactSizeIterator for ArrayDrain<'a, T> {}
+
+impl <'a, T> Drop for ArrayDrain<'a, T> {
+    fn drop(&mut self) {
+        while let Some(_) = self.next() {}
+        let gap = self.end - self.start;
+        let after = self.len - self.end;
+        if gap > 0 {
+            unsafe {
+                if self.start < after {
+                    for i in (0..self.start).rev() {
+                        ptr::copy_nonoverlapping(self.slot(i), self.slot(i + gap), 1);
+                    }
+                    *self.head = (*self.head + gap) % self.data.cap();
+                } else {
+                    for i in self.end..self.len {
+                        ptr::copy_nonoverlapping(self.slot(i), self.slot(i - gap), 1);
+                    }
+                }
+            }
+        }
+        *self.size = self.len - gap;
+        if self.len > 0 {
+            *self.tail = (*self.head + *self.size) % self.data.cap();
+        }
+    }
+}
+
+pub struct ArrayIter<'a, T: 'a> {
+    data: *const T,
+    capacity: usize,
+    index: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a T>
+}
+
+impl <'a, T> ArrayIter<'a, T> {
+    fn new(data: &'a RawVec<T>, head: usize, size: usize) -> ArrayIter<'a, T> {
+        ArrayIter {
+            data: data.ptr(),
+            capacity: data.cap(),
+            index: head,
+            remaining: size,
+            _marker: PhantomData
+        }
+    }
+}
+
+impl <'a, T> Iterator for ArrayIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            None
+        } else {
+            let item = unsafe { &*self.data.offset(self.index as isize) };
+            self.index += 1;
+            if self.index == self.capacity {
+                self.index = 0;
+            }
+            self.remaining -= 1;
+            Some(item)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl <'a, T> ExactSizeIterator for ArrayIter<'a, T> {}
+
+pub struct ArrayIterMut<'a, T: 'a> {
+    data: *mut T,
+    capacity: usize,
+    index: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a mut T>
+}
+
+impl <'a, T> ArrayIterMut<'a, T> {
+    fn new(data: &'a mut RawVec<T>, head: usize, size: usize) -> ArrayIterMut<'a, T> {
+        ArrayIterMut {
+            data: data.ptr(),
+            capacity: data.cap(),
+            index: head,
+            remaining: size,
+            _marker: PhantomData
+        }
+    }
+}
+
+impl <'a, T> Iterator for ArrayIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            None
+        } else {
+            let item = unsafe { &mut *self.data.offset(self.index as isize) };
+            self.index += 1;
+            if self.index == self.capacity {
+                self.index = 0;
+            }
+            self.remaining -= 1;
+            Some(item)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl <'a, T> ExactSizeIterator for ArrayIterMut<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    mod non_resizable_array_queue {
+        use std::mem;
+        use std::rc::Rc;
+
+        use super::super::*;
+
+        #[test]
+        fn deque_from_empty_queue() {
+            let mut queue: NonResizableArrayQueue<i32> = NonResizableArrayQueue::new(16);
+
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn enqueue_one_item() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn enqueue_three_items_one_by_one() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), None);
+
+            queue.enqueue(20);
+
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), None);
+
+            queue.enqueue(30);
+
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn enqueue_many_items_deque_many_items() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn len_of_queue() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            assert_eq!(queue.len(), 0);
+            assert!(queue.is_empty());
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.len(), 2);
+            assert!(!queue.is_empty());
+
+            queue.deque();
+
+            assert_eq!(queue.len(), 1);
+        }
+
+        #[test]
+        fn peek_from_empty_queue() {
+            let mut queue: NonResizableArrayQueue<i32> = NonResizableArrayQueue::new(16);
+
+            assert_eq!(queue.peek(), None);
+            assert_eq!(queue.peek_mut(), None);
+        }
+
+        #[test]
+        fn peek_does_not_remove_item() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.peek(), Some(&10));
+            assert_eq!(queue.len(), 2);
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.peek(), Some(&20));
+        }
+
+        #[test]
+        fn peek_mut_changes_first_item() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            queue.peek_mut().map(|item| *item = 30);
+
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), Some(20));
+        }
+
+        #[test]
+        fn capacity_is_rounded_up_to_power_of_two() {
+            let queue: NonResizableArrayQueue<i32> = NonResizableArrayQueue::new(10);
+
+            assert_eq!(queue.capacity(), 16);
+        }
+
+        #[test]
+        fn try_enqueue_into_full_queue() {
+            let mut queue = NonResizableArrayQueue::new(4);
+
+            for i in 0..4 {
+                assert_eq!(queue.try_enqueue(i), Ok(()));
+            }
+
+            assert!(queue.is_full());
+            assert_eq!(queue.try_enqueue(4), Err(Full(4)));
+
+            for i in 0..4 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn try_enqueue_after_deque_from_full_queue() {
+            let mut queue = NonResizableArrayQueue::new(4);
+
+            for i in 0..4 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.deque(), Some(0));
+            assert!(!queue.is_full());
+            assert_eq!(queue.try_enqueue(4), Ok(()));
+
+            for i in 1..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn enqueue_into_full_queue() {
+            let mut queue = NonResizableArrayQueue::new(4);
+
+            for i in 0..5 {
+                queue.enqueue(i);
+            }
+        }
+
+        #[test]
+        fn offer_with_reject_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::Reject);
+
+            for i in 0..4 {
+                assert_eq!(queue.offer(i), Ok(None));
+            }
+
+            assert_eq!(queue.offer(4), Err(Full(4)));
+
+            for i in 0..4 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn enqueue_into_full_queue_with_reject_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::Reject);
+
+            for i in 0..5 {
+                queue.enqueue(i);
+            }
+        }
+
+        #[test]
+        fn offer_with_overwrite_oldest_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::OverwriteOldest);
+
+            for i in 0..4 {
+                assert_eq!(queue.offer(i), Ok(None));
+            }
+
+            assert_eq!(queue.offer(4), Ok(Some(0)));
+            assert_eq!(queue.offer(5), Ok(Some(1)));
+            assert_eq!(queue.len(), 4);
+
+            for i in 2..6 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn enqueue_keeps_last_items_with_overwrite_oldest_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(16, OverflowPolicy::OverwriteOldest);
+
+            for i in 0..100 {
+                queue.enqueue(i);
+            }
+
+            for i in 84..100 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn offer_with_drop_newest_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::DropNewest);
+
+            for i in 0..4 {
+                assert_eq!(queue.offer(i), Ok(None));
+            }
+
+            assert_eq!(queue.offer(4), Ok(Some(4)));
+            queue.enqueue(5);
+
+            for i in 0..4 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        #[should_panic]
+        fn offer_with_panic_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::Panic);
+
+            for i in 0..5 {
+                let _ = queue.offer(i);
+            }
+        }
+
+        #[test]
+        fn try_enqueue_ignores_overflow_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::OverwriteOldest);
+
+            for i in 0..4 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.try_enqueue(4), Err(Full(4)));
+            assert_eq!(queue.deque(), Some(0));
+        }
+
+        #[test]
+        fn iter_over_items() {
+            let mut queue = NonResizableArrayQueue::new(32);
+
+            for i in 0..30 {
+                queue.enqueue(i);
+            }
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 30..35 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..35).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 30);
+        }
+
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = NonResizableArrayQueue::new(32);
+
+            for i in 0..30 {
+                queue.enqueue(i);
+            }
+
+            for item in &mut queue {
+                *item *= 2;
+            }
+
+            for i in 0..30 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
+        }
+
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = NonResizableArrayQueue::new(32);
+
+            for i in 0..30 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..30).collect::<Vec<i32>>());
+        }
 
         #[test]
-        fn deque_from_empty_queue() {
-            let mut queue: NonResizableArrayQueue<i32> = NonResizableArrayQueue::new(16);
+        fn enqueue_slice_wraps_around() {
+            let mut queue = NonResizableArrayQueue::new(16);
+
+            queue.enqueue_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+            for i in 0..8 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            queue.enqueue_slice(&[10, 11, 12, 13, 14, 15, 16, 17, 18, 19]);
 
+            assert_eq!(queue.len(), 12);
+            for i in 8..20 {
+                assert_eq!(queue.deque(), Some(i));
+            }
             assert_eq!(queue.deque(), None);
         }
 
         #[test]
-        fn enqueue_one_item() {
+        fn enqueue_slice_that_does_not_fit_follows_overflow_policy() {
+            let mut queue = NonResizableArrayQueue::with_policy(4, OverflowPolicy::OverwriteOldest);
+
+            queue.enqueue_slice(&[0, 1, 2]);
+            queue.enqueue_slice(&[3, 4, 5]);
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![2, 3, 4, 5]);
+        }
+
+        #[test]
+        fn deque_into_wraps_around() {
             let mut queue = NonResizableArrayQueue::new(16);
+            let mut items = vec![];
 
-            queue.enqueue(10);
+            queue.enqueue_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+            assert_eq!(queue.deque_into(&mut items, 10), 10);
+            queue.enqueue_slice(&[12, 13, 14, 15, 16, 17, 18, 19]);
 
-            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque_into(&mut items, 20), 10);
+            assert_eq!(items, (0..20).collect::<Vec<i32>>());
             assert_eq!(queue.deque(), None);
         }
 
         #[test]
-        fn enqueue_three_items_one_by_one() {
+        fn collect_into_queue() {
+            let mut queue = (0..10).collect::<NonResizableArrayQueue<i32>>();
+
+            assert_eq!(queue.capacity(), 16);
+
+            queue.extend(10..16);
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..16).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn collect_more_items_than_size_hint() {
+            let queue = (0..100).filter(|i| i % 2 == 0).map(Rc::new).collect::<NonResizableArrayQueue<Rc<i32>>>();
+
+            assert_eq!(queue.len(), 50);
+            assert_eq!(queue.capacity(), 64);
+            assert_eq!(queue.into_iter().map(|item| *item).collect::<Vec<i32>>(), (0..100).filter(|i| i % 2 == 0).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn drain_middle_of_wrapped_queue() {
             let mut queue = NonResizableArrayQueue::new(16);
 
-            queue.enqueue(10);
+            for i in 0..12 {
+                queue.enqueue(i);
+            }
+            for _ in 0..8 {
+                queue.deque();
+            }
+            for i in 12..22 {
+                queue.enqueue(i);
+            }
 
-            assert_eq!(queue.deque(), Some(10));
-            assert_eq!(queue.deque(), None);
+            assert_eq!(queue.drain(3..6).collect::<Vec<i32>>(), vec![11, 12, 13]);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![8, 9, 10, 14, 15, 16, 17, 18, 19, 20, 21]);
+            assert_eq!(queue.drain(6..9).next(), Some(17));
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![8, 9, 10, 14, 15, 16, 20, 21]);
 
-            queue.enqueue(20);
+            for i in 22..30 {
+                queue.enqueue(i);
+            }
 
-            assert_eq!(queue.deque(), Some(20));
-            assert_eq!(queue.deque(), None);
+            assert_eq!(queue.len(), 16);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![8, 9, 10, 14, 15, 16, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29]);
+        }
 
-            queue.enqueue(30);
+        #[test]
+        fn drain_drops_items() {
+            let item = Rc::new(0);
+            {
+                let mut queue = NonResizableArrayQueue::new(8);
 
-            assert_eq!(queue.deque(), Some(30));
-            assert_eq!(queue.deque(), None);
+                for _ in 0..8 {
+                    queue.enqueue(item.clone());
+                }
+
+                assert_eq!(queue.drain(2..6).count(), 4);
+                queue.drain(1..3);
+
+                assert_eq!(Rc::strong_count(&item), 3);
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
         }
 
         #[test]
-        fn enqueue_many_items_deque_many_items() {
+        fn leaked_drain_keeps_items_before_range() {
             let mut queue = NonResizableArrayQueue::new(16);
 
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            mem::forget(queue.drain(3..6));
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 1, 2]);
+
             queue.enqueue(10);
-            queue.enqueue(20);
-            queue.enqueue(30);
 
-            assert_eq!(queue.deque(), Some(10));
-            assert_eq!(queue.deque(), Some(20));
-            assert_eq!(queue.deque(), Some(30));
-            assert_eq!(queue.deque(), None);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 1, 2, 10]);
         }
     }
 
@@ -294,5 +1265,297 @@ mod tests {
                 assert_eq!(queue.deque(), Some(i));
             }
         }
+
+        #[test]
+        fn len_of_queue() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            assert_eq!(queue.len(), 0);
+            assert!(queue.is_empty());
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.len(), 2);
+            assert!(!queue.is_empty());
+
+            queue.deque();
+
+            assert_eq!(queue.len(), 1);
+        }
+
+        #[test]
+        fn peek_from_empty_queue() {
+            let mut queue: ResizableArrayQueue<i32> = ResizableArrayQueue::new(16);
+
+            assert_eq!(queue.peek(), None);
+            assert_eq!(queue.peek_mut(), None);
+        }
+
+        #[test]
+        fn peek_does_not_remove_item() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.peek(), Some(&10));
+            assert_eq!(queue.len(), 2);
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.peek(), Some(&20));
+        }
+
+        #[test]
+        fn peek_mut_changes_first_item() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            queue.peek_mut().map(|item| *item = 30);
+
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), Some(20));
+        }
+
+        fn enqueue_deque_with_policy<P: ResizePolicy>(policy: P) -> ResizableArrayQueue<i32, P> {
+            let mut queue = ResizableArrayQueue::with_policy(10, policy);
+
+            for i in 0..100 {
+                queue.enqueue(i);
+            }
+
+            for i in 0..50 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+
+            for i in 100..200 {
+                queue.enqueue(i);
+            }
+
+            for i in 50..190 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+
+            queue
+        }
+
+        #[test]
+        fn doubling_policy() {
+            assert_eq!(Doubling.grow(16), 32);
+            assert_eq!(Doubling.grow(0), 1);
+
+            let queue = enqueue_deque_with_policy(Doubling);
+
+            assert_eq!(queue.len(), 10);
+        }
+
+        #[test]
+        fn one_and_half_policy() {
+            assert_eq!(OneAndHalf.grow(16), 24);
+            assert_eq!(OneAndHalf.grow(1), 2);
+
+            let queue = enqueue_deque_with_policy(OneAndHalf);
+
+            assert_eq!(queue.len(), 10);
+        }
+
+        #[test]
+        fn fixed_increment_policy() {
+            assert_eq!(FixedIncrement(10).grow(16), 26);
+
+            let mut queue = ResizableArrayQueue::with_policy(10, FixedIncrement(10));
+
+            for i in 0..25 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.capacity(), 30);
+
+            for i in 0..25 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+        }
+
+        #[test]
+        fn never_shrink_policy() {
+            let queue = enqueue_deque_with_policy(NeverShrink(Doubling));
+
+            assert_eq!(queue.len(), 10);
+            assert_eq!(queue.capacity(), 160);
+        }
+
+        #[test]
+        fn hysteresis_shrink_policy() {
+            let policy = HysteresisShrink::new(Doubling, 8, 32);
+
+            assert_eq!(policy.shrink(16, 128), Some(64));
+            assert_eq!(policy.shrink(17, 128), None);
+            assert_eq!(policy.shrink(2, 32), None);
+            assert_eq!(policy.shrink(2, 40), Some(32));
+
+            let queue = enqueue_deque_with_policy(policy);
+
+            assert_eq!(queue.len(), 10);
+            assert_eq!(queue.capacity(), 40);
+        }
+
+        #[test]
+        #[should_panic]
+        fn hysteresis_shrink_policy_with_thrashing_ratio() {
+            HysteresisShrink::new(Doubling, 2, 16);
+        }
+
+        #[test]
+        fn iter_over_items() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 40..45 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..45).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 40);
+        }
+
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+
+            for item in &mut queue {
+                *item *= 2;
+            }
+
+            for i in 0..40 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
+        }
+
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..40).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn drain_range_of_items() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.drain(5..10).collect::<Vec<i32>>(), vec![5, 6, 7, 8, 9]);
+            assert_eq!(queue.len(), 40 - 5);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (0..5).chain(10..40).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn drop_partially_consumed_drain() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.drain(0..10).next(), Some(0));
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (10..40).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn drain_middle_of_queue() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.drain(3..6).collect::<Vec<i32>>(), vec![3, 4, 5]);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 1, 2, 6, 7, 8, 9]);
+            assert_eq!(queue.drain(1..2).collect::<Vec<i32>>(), vec![1]);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 2, 6, 7, 8, 9]);
+            assert_eq!(queue.deque(), Some(0));
+
+            queue.enqueue(10);
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![2, 6, 7, 8, 9, 10]);
+        }
+
+        #[test]
+        fn drain_keeps_capacity() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            for i in 0..64 {
+                queue.enqueue(i);
+            }
+            let capacity = queue.capacity();
+
+            assert_eq!(queue.drain(1..63).count(), 62);
+            assert_eq!(queue.capacity(), capacity);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 63]);
+        }
+
+        #[test]
+        fn drain_empty_queue() {
+            let mut queue: ResizableArrayQueue<i32> = ResizableArrayQueue::new(0);
+
+            assert_eq!(queue.drain(0..0).next(), None);
+            assert!(queue.is_empty());
+        }
+
+        #[test]
+        fn enqueue_slice_grows_queue() {
+            let mut queue = ResizableArrayQueue::new(16);
+
+            queue.enqueue_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+            for i in 0..8 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            queue.enqueue_slice(&(10..50).collect::<Vec<i32>>());
+
+            assert_eq!(queue.len(), 42);
+            assert_eq!(queue.capacity(), 64);
+            for i in 8..50 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn deque_into_shrinks_queue() {
+            let mut queue = ResizableArrayQueue::new(16);
+            let mut items = vec![];
+
+            queue.enqueue_slice(&(0..200).collect::<Vec<i32>>());
+
+            assert_eq!(queue.deque_into(&mut items, 170), 170);
+            assert_eq!(queue.capacity(), 128);
+            assert_eq!(queue.deque_into(&mut items, 100), 30);
+            assert_eq!(items, (0..200).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn collect_into_queue() {
+            let mut queue = (0..10).collect::<ResizableArrayQueue<i32>>();
+
+            queue.extend(10..40);
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..40).collect::<Vec<i32>>());
+        }
     }
 }
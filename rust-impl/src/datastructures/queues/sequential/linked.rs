@@ -1,10 +1,17 @@
+extern crate alloc;
+
+use self::alloc::raw_vec::RawVec;
+
 use std::fmt;
+use std::mem;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
 
 use std::rc::Rc;
 use std::cell::RefCell;
-use std::ptr::Shared;
+use std::ptr::{self, Shared};
 
-use super::Queue;
+use super::super::{IntoIter, Queue};
 
 type RcRefCellLink<T> = Option<Rc<RefCell<T>>>;
 
@@ -28,14 +35,30 @@ impl <E> RefCellNode<E> {
 #[derive(Default, Debug)]
 pub struct RcRefCellLinkedQueue<E> {
     head: RcRefCellLink<RefCellNode<E>>,
-    tail: RcRefCellLink<RefCellNode<E>>
+    tail: RcRefCellLink<RefCellNode<E>>,
+    size: usize
 }
 
 impl <E> RcRefCellLinkedQueue<E> {
     pub fn new() -> RcRefCellLinkedQueue<E> {
         RcRefCellLinkedQueue {
             head: None,
-            tail: None
+            tail: None,
+            size: 0
+        }
+    }
+
+    pub fn iter(&self) -> RcRefCellLinkedIter<E> {
+        RcRefCellLinkedIter {
+            node: self.head.as_ref().map(|head| head.as_ptr()),
+            _marker: PhantomData
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> RcRefCellLinkedIterMut<E> {
+        RcRefCellLinkedIterMut {
+            node: self.head.as_ref().map(|head| head.as_ptr()),
+            _marker: PhantomData
         }
     }
 }
@@ -47,6 +70,7 @@ impl <E> Queue<E> for RcRefCellLinkedQueue<E> {
                 Some(new_head) => self.head = Some(new_head),
                 None => { self.tail.take(); }
             }
+            self.size -= 1;
             Rc::try_unwrap(old_head).ok().unwrap().into_inner().item
         })
     }
@@ -57,7 +81,20 @@ impl <E> Queue<E> for RcRefCellLinkedQueue<E> {
             Some(old_tail) => old_tail.borrow_mut().next = Some(node.clone()),
             None => self.head = Some(node.clone())
         }
-        self.tail = Some(node)
+        self.tail = Some(node);
+        self.size += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&E> {
+        self.head.as_ref().map(|head| unsafe { &(*head.as_ptr()).item })
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut E> {
+        self.head.as_ref().map(|head| unsafe { &mut (*head.as_ptr()).item })
     }
 }
 
@@ -67,6 +104,80 @@ impl <E> Drop for RcRefCellLinkedQueue<E> {
     }
 }
 
+impl <E> FromIterator<E> for RcRefCellLinkedQueue<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(items: I) -> Self {
+        let items = items.into_iter();
+        let mut queue = RcRefCellLinkedQueue::new();
+        queue.enqueue_all(items);
+        queue
+    }
+}
+
+impl <E> Extend<E> for RcRefCellLinkedQueue<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <E> IntoIterator for RcRefCellLinkedQueue<E> {
+    type Item = E;
+    type IntoIter = IntoIter<E, RcRefCellLinkedQueue<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, E> IntoIterator for &'a RcRefCellLinkedQueue<E> {
+    type Item = &'a E;
+    type IntoIter = RcRefCellLinkedIter<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl <'a, E> IntoIterator for &'a mut RcRefCellLinkedQueue<E> {
+    type Item = &'a mut E;
+    type IntoIter = RcRefCellLinkedIterMut<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct RcRefCellLinkedIter<'a, E: 'a> {
+    node: Option<*mut RefCellNode<E>>,
+    _marker: PhantomData<&'a E>
+}
+
+impl <'a, E> Iterator for RcRefCellLinkedIter<'a, E> {
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<&'a E> {
+        self.node.map(|node| unsafe {
+            self.node = (*node).next.as_ref().map(|next| next.as_ptr());
+            &(*node).item
+        })
+    }
+}
+
+pub struct RcRefCellLinkedIterMut<'a, E: 'a> {
+    node: Option<*mut RefCellNode<E>>,
+    _marker: PhantomData<&'a mut E>
+}
+
+impl <'a, E> Iterator for RcRefCellLinkedIterMut<'a, E> {
+    type Item = &'a mut E;
+
+    fn next(&mut self) -> Option<&'a mut E> {
+        self.node.map(|node| unsafe {
+            self.node = (*node).next.as_ref().map(|next| next.as_ptr());
+            &mut (*node).item
+        })
+    }
+}
+
 type SharedLink<T> = Option<Shared<T>>;
 
 struct SharedNode<E> {
@@ -91,21 +202,37 @@ impl <E: fmt::Debug> fmt::Debug for SharedNode<E> {
     }
 }
 
-pub struct SharedLinkedQueue<E: Copy> {
+pub struct SharedLinkedQueue<E> {
     head: SharedLink<SharedNode<E>>,
-    tail: SharedLink<SharedNode<E>>
+    tail: SharedLink<SharedNode<E>>,
+    size: usize
 }
 
-impl <E: Copy> SharedLinkedQueue<E> {
+impl <E> SharedLinkedQueue<E> {
     pub fn new() -> SharedLinkedQueue<E> {
         SharedLinkedQueue {
             head: None,
-            tail: None
+            tail: None,
+            size: 0
+        }
+    }
+
+    pub fn iter(&self) -> SharedLinkedIter<E> {
+        SharedLinkedIter {
+            node: self.head,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> SharedLinkedIterMut<E> {
+        SharedLinkedIterMut {
+            node: self.head,
+            _marker: PhantomData
         }
     }
 }
 
-impl <E: fmt::Debug + Copy> fmt::Debug for SharedLinkedQueue<E> {
+impl <E: fmt::Debug> fmt::Debug for SharedLinkedQueue<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut node = self.head;
         while let Some(item) = node {
@@ -118,16 +245,16 @@ impl <E: fmt::Debug + Copy> fmt::Debug for SharedLinkedQueue<E> {
     }
 }
 
-impl <E: Copy> Queue<E> for SharedLinkedQueue<E> {
+impl <E> Queue<E> for SharedLinkedQueue<E> {
     fn deque(&mut self) -> Option<E> {
         self.head.take().map(|mut head| unsafe {
             match head.as_mut().next.take() {
                 Some(new_head) => self.head = Some(new_head),
                 None => self.tail = None
             }
-            let item = head.as_ref().item;
-            drop(Box::from_raw(head.as_ptr()));
-            item
+            let node = Box::from_raw(head.as_ptr());
+            self.size -= 1;
+            node.item
         })
     }
 
@@ -137,13 +264,343 @@ impl <E: Copy> Queue<E> for SharedLinkedQueue<E> {
             Some(mut tail) => unsafe { tail.as_mut().next = node.clone(); },
             None => self.head = node.clone()
         }
-        self.tail = node
+        self.tail = node;
+        self.size += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&E> {
+        self.head.as_ref().map(|head| unsafe { &head.as_ref().item })
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut E> {
+        self.head.as_mut().map(|head| unsafe { &mut head.as_mut().item })
+    }
+}
+
+impl <E> Drop for SharedLinkedQueue<E> {
+    fn drop(&mut self) {
+        while let Some(_) = self.deque() {}
+    }
+}
+
+impl <E> FromIterator<E> for SharedLinkedQueue<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(items: I) -> Self {
+        let items = items.into_iter();
+        let mut queue = SharedLinkedQueue::new();
+        queue.enqueue_all(items);
+        queue
+    }
+}
+
+impl <E> Extend<E> for SharedLinkedQueue<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <E> IntoIterator for SharedLinkedQueue<E> {
+    type Item = E;
+    type IntoIter = IntoIter<E, SharedLinkedQueue<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, E> IntoIterator for &'a SharedLinkedQueue<E> {
+    type Item = &'a E;
+    type IntoIter = SharedLinkedIter<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl <'a, E> IntoIterator for &'a mut SharedLinkedQueue<E> {
+    type Item = &'a mut E;
+    type IntoIter = SharedLinkedIterMut<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct SharedLinkedIter<'a, E: 'a> {
+    node: SharedLink<SharedNode<E>>,
+    _marker: PhantomData<&'a E>
+}
+
+impl <'a, E> Iterator for SharedLinkedIter<'a, E> {
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<&'a E> {
+        self.node.map(|node| unsafe {
+            self.node = node.as_ref().next;
+            &(*node.as_ptr()).item
+        })
+    }
+}
+
+pub struct SharedLinkedIterMut<'a, E: 'a> {
+    node: SharedLink<SharedNode<E>>,
+    _marker: PhantomData<&'a mut E>
+}
+
+impl <'a, E> Iterator for SharedLinkedIterMut<'a, E> {
+    type Item = &'a mut E;
+
+    fn next(&mut self) -> Option<&'a mut E> {
+        self.node.map(|node| unsafe {
+            self.node = node.as_ref().next;
+            &mut (*node.as_ptr()).item
+        })
+    }
+}
+
+// nodes are allocated without an item, so they are never dropped as a whole
+struct PooledNode<E> {
+    item: E,
+    next: SharedLink<PooledNode<E>>
+}
+
+impl <E> PooledNode<E> {
+    fn allocate() -> SharedLink<PooledNode<E>> {
+        let storage = RawVec::with_capacity(1);
+        let node: *mut PooledNode<E> = storage.ptr();
+        mem::forget(storage);
+        unsafe { ptr::write(&mut (*node).next, None); }
+        Shared::new(node)
+    }
+
+    unsafe fn deallocate(node: Shared<PooledNode<E>>) {
+        drop(RawVec::from_raw_parts(node.as_ptr(), 1));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub hits: usize,
+    pub misses: usize
+}
+
+pub struct PooledSharedLinkedQueue<E> {
+    head: SharedLink<PooledNode<E>>,
+    tail: SharedLink<PooledNode<E>>,
+    size: usize,
+    pool: SharedLink<PooledNode<E>>,
+    pooled: usize,
+    pool_capacity: usize,
+    stats: PoolStats
+}
+
+impl <E> PooledSharedLinkedQueue<E> {
+    pub fn new(pool_capacity: usize) -> PooledSharedLinkedQueue<E> {
+        PooledSharedLinkedQueue {
+            head: None,
+            tail: None,
+            size: 0,
+            pool: None,
+            pooled: 0,
+            pool_capacity: pool_capacity,
+            stats: PoolStats::default()
+        }
+    }
+
+    pub fn with_prewarm(pool_capacity: usize, prewarm: usize) -> PooledSharedLinkedQueue<E> {
+        assert!(prewarm <= pool_capacity, "cannot prewarm {} nodes into a pool of {}", prewarm, pool_capacity);
+        let mut queue = PooledSharedLinkedQueue::new(pool_capacity);
+        for _ in 0..prewarm {
+            PooledNode::allocate().map(|node| queue.release(node));
+        }
+        queue
+    }
+
+    pub fn pool_capacity(&self) -> usize {
+        self.pool_capacity
+    }
+
+    pub fn pooled(&self) -> usize {
+        self.pooled
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = PoolStats::default();
+    }
+
+    pub fn iter(&self) -> PooledSharedLinkedIter<E> {
+        PooledSharedLinkedIter {
+            node: self.head,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> PooledSharedLinkedIterMut<E> {
+        PooledSharedLinkedIterMut {
+            node: self.head,
+            _marker: PhantomData
+        }
+    }
+
+    fn acquire(&mut self, item: E) -> SharedLink<PooledNode<E>> {
+        let node = match self.pool.take() {
+            Some(mut node) => {
+                self.pool = unsafe { node.as_mut().next.take() };
+                self.pooled -= 1;
+                self.stats.hits += 1;
+                Some(node)
+            }
+            None => {
+                self.stats.misses += 1;
+                PooledNode::allocate()
+            }
+        };
+        node.map(|mut node| unsafe {
+            ptr::write(&mut node.as_mut().item, item);
+            node
+        })
+    }
+
+    // the item of a released node has already been moved out
+    fn release(&mut self, mut node: Shared<PooledNode<E>>) {
+        if self.pooled < self.pool_capacity {
+            unsafe { node.as_mut().next = self.pool.take(); }
+            self.pool = Some(node);
+            self.pooled += 1;
+        } else {
+            unsafe { PooledNode::deallocate(node); }
+        }
+    }
+}
+
+impl <E> Queue<E> for PooledSharedLinkedQueue<E> {
+    fn deque(&mut self) -> Option<E> {
+        self.head.take().map(|mut head| unsafe {
+            match head.as_mut().next.take() {
+                Some(new_head) => self.head = Some(new_head),
+                None => self.tail = None
+            }
+            let item = ptr::read(&head.as_ref().item);
+            self.release(head);
+            self.size -= 1;
+            item
+        })
+    }
+
+    fn enqueue(&mut self, item: E) {
+        let node = self.acquire(item);
+        match self.tail.take() {
+            Some(mut tail) => unsafe { tail.as_mut().next = node.clone(); },
+            None => self.head = node.clone()
+        }
+        self.tail = node;
+        self.size += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&E> {
+        self.head.as_ref().map(|head| unsafe { &head.as_ref().item })
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut E> {
+        self.head.as_mut().map(|head| unsafe { &mut head.as_mut().item })
     }
 }
 
-impl <E: Copy> Drop for SharedLinkedQueue<E> {
+impl <E> Drop for PooledSharedLinkedQueue<E> {
     fn drop(&mut self) {
         while let Some(_) = self.deque() {}
+        let mut node = self.pool.take();
+        while let Some(current) = node {
+            unsafe {
+                node = current.as_ref().next;
+                PooledNode::deallocate(current);
+            }
+        }
+    }
+}
+
+impl <E> FromIterator<E> for PooledSharedLinkedQueue<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(items: I) -> Self {
+        let items = items.into_iter();
+        let mut queue = PooledSharedLinkedQueue::new(items.size_hint().0);
+        queue.enqueue_all(items);
+        queue
+    }
+}
+
+impl <E> Extend<E> for PooledSharedLinkedQueue<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <E> IntoIterator for PooledSharedLinkedQueue<E> {
+    type Item = E;
+    type IntoIter = IntoIter<E, PooledSharedLinkedQueue<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, E> IntoIterator for &'a PooledSharedLinkedQueue<E> {
+    type Item = &'a E;
+    type IntoIter = PooledSharedLinkedIter<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl <'a, E> IntoIterator for &'a mut PooledSharedLinkedQueue<E> {
+    type Item = &'a mut E;
+    type IntoIter = PooledSharedLinkedIterMut<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct PooledSharedLinkedIter<'a, E: 'a> {
+    node: SharedLink<PooledNode<E>>,
+    _marker: PhantomData<&'a E>
+}
+
+impl <'a, E> Iterator for PooledSharedLinkedIter<'a, E> {
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<&'a E> {
+        self.node.map(|node| unsafe {
+            self.node = node.as_ref().next;
+            &(*node.as_ptr()).item
+        })
+    }
+}
+
+pub struct PooledSharedLinkedIterMut<'a, E: 'a> {
+    node: SharedLink<PooledNode<E>>,
+    _marker: PhantomData<&'a mut E>
+}
+
+impl <'a, E> Iterator for PooledSharedLinkedIterMut<'a, E> {
+    type Item = &'a mut E;
+
+    fn next(&mut self) -> Option<&'a mut E> {
+        self.node.map(|node| unsafe {
+            self.node = node.as_ref().next;
+            &mut (*node.as_ptr()).item
+        })
     }
 }
 
@@ -204,9 +661,121 @@ mod tests {
             assert_eq!(queue.deque(), Some(30));
             assert_eq!(queue.deque(), None);
         }
+
+        #[test]
+        fn len_of_queue() {
+            let mut queue = RcRefCellLinkedQueue::new();
+
+            assert_eq!(queue.len(), 0);
+            assert!(queue.is_empty());
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.len(), 2);
+            assert!(!queue.is_empty());
+
+            queue.deque();
+
+            assert_eq!(queue.len(), 1);
+        }
+
+        #[test]
+        fn peek_from_empty_queue() {
+            let mut queue: RcRefCellLinkedQueue<i32> = RcRefCellLinkedQueue::new();
+
+            assert_eq!(queue.peek(), None);
+            assert_eq!(queue.peek_mut(), None);
+        }
+
+        #[test]
+        fn peek_does_not_remove_item() {
+            let mut queue = RcRefCellLinkedQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.peek(), Some(&10));
+            assert_eq!(queue.len(), 2);
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.peek(), Some(&20));
+        }
+
+        #[test]
+        fn peek_mut_changes_first_item() {
+            let mut queue = RcRefCellLinkedQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            queue.peek_mut().map(|item| *item = 30);
+
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), Some(20));
+        }
+
+        #[test]
+        fn iter_over_items() {
+            let mut queue = RcRefCellLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 10..15 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..15).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 10);
+        }
+
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = RcRefCellLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            for item in &mut queue {
+                *item *= 2;
+            }
+
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
+        }
+
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = RcRefCellLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..10).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn collect_into_queue() {
+            let mut queue = (0..10).collect::<RcRefCellLinkedQueue<i32>>();
+            let mut items = vec![];
+
+            queue.extend(10..20);
+            queue.enqueue_slice(&[20, 21]);
+
+            assert_eq!(queue.deque_into(&mut items, 30), 22);
+            assert_eq!(items, (0..22).collect::<Vec<i32>>());
+        }
     }
 
     mod linked_shared_queue {
+        use std::rc::Rc;
+
         use super::super::SharedLinkedQueue;
         use super::super::super::Queue;
 
@@ -260,5 +829,346 @@ mod tests {
             assert_eq!(queue.deque(), Some(30));
             assert_eq!(queue.deque(), None);
         }
+
+        #[test]
+        fn len_of_queue() {
+            let mut queue = SharedLinkedQueue::new();
+
+            assert_eq!(queue.len(), 0);
+            assert!(queue.is_empty());
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.len(), 2);
+            assert!(!queue.is_empty());
+
+            queue.deque();
+
+            assert_eq!(queue.len(), 1);
+        }
+
+        #[test]
+        fn peek_from_empty_queue() {
+            let mut queue: SharedLinkedQueue<i32> = SharedLinkedQueue::new();
+
+            assert_eq!(queue.peek(), None);
+            assert_eq!(queue.peek_mut(), None);
+        }
+
+        #[test]
+        fn peek_does_not_remove_item() {
+            let mut queue = SharedLinkedQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            assert_eq!(queue.peek(), Some(&10));
+            assert_eq!(queue.len(), 2);
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.peek(), Some(&20));
+        }
+
+        #[test]
+        fn peek_mut_changes_first_item() {
+            let mut queue = SharedLinkedQueue::new();
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+
+            queue.peek_mut().map(|item| *item = 30);
+
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), Some(20));
+        }
+
+        #[test]
+        fn enqueue_deque_non_copy_items() {
+            let mut queue = SharedLinkedQueue::new();
+
+            queue.enqueue(vec![10]);
+            queue.enqueue(vec![20, 30]);
+
+            assert_eq!(queue.deque(), Some(vec![10]));
+            assert_eq!(queue.deque(), Some(vec![20, 30]));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let mut queue = SharedLinkedQueue::new();
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                assert_eq!(queue.deque().map(|item| *item), Some(0));
+                assert_eq!(Rc::strong_count(&item), 10);
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn iter_over_items() {
+            let mut queue = SharedLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 10..15 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..15).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 10);
+        }
+
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = SharedLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            for item in &mut queue {
+                *item *= 2;
+            }
+
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
+        }
+
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = SharedLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..10).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn drain_range_of_items() {
+            let mut queue = SharedLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.drain(5..10).collect::<Vec<i32>>(), vec![5, 6, 7, 8, 9]);
+            assert_eq!(queue.len(), 10 - 5);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 1, 2, 3, 4]);
+        }
+
+        #[test]
+        fn drop_partially_consumed_drain() {
+            let mut queue = SharedLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.drain(0..10).next(), Some(0));
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), Vec::<i32>::new());
+        }
+
+        #[test]
+        fn drain_middle_of_queue() {
+            let mut queue = SharedLinkedQueue::new();
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.drain(3..6).collect::<Vec<i32>>(), vec![3, 4, 5]);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 1, 2, 6, 7, 8, 9]);
+
+            assert_eq!(queue.drain(1..4).next(), Some(1));
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), vec![0, 7, 8, 9]);
+        }
+
+        #[test]
+        fn collect_into_queue() {
+            let mut queue = (0..10).collect::<SharedLinkedQueue<i32>>();
+            let mut items = vec![];
+
+            queue.extend(10..20);
+            queue.enqueue_slice(&[20, 21]);
+
+            assert_eq!(queue.deque_into(&mut items, 30), 22);
+            assert_eq!(items, (0..22).collect::<Vec<i32>>());
+        }
+    }
+
+    mod pooled_shared_linked_queue {
+        use std::rc::Rc;
+
+        use super::super::{PoolStats, PooledSharedLinkedQueue};
+        use super::super::super::Queue;
+
+        #[test]
+        fn deque_item_from_empty_queue() {
+            let mut queue: PooledSharedLinkedQueue<i32> = PooledSharedLinkedQueue::new(16);
+
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn enqueue_many_items_deque_many_items() {
+            let mut queue = PooledSharedLinkedQueue::new(16);
+
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
+
+            assert_eq!(queue.len(), 3);
+            assert_eq!(queue.peek(), Some(&10));
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        fn reuse_released_nodes() {
+            let mut queue = PooledSharedLinkedQueue::new(16);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+
+            assert_eq!(queue.pooled(), 10);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.pooled(), 0);
+            assert_eq!(queue.stats(), PoolStats { hits: 10, misses: 10 });
+        }
+
+        #[test]
+        fn pool_does_not_exceed_capacity() {
+            let mut queue = PooledSharedLinkedQueue::new(4);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+
+            assert_eq!(queue.pooled(), 4);
+        }
+
+        #[test]
+        fn prewarm_pool() {
+            let mut queue = PooledSharedLinkedQueue::with_prewarm(16, 8);
+
+            assert_eq!(queue.pooled(), 8);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.stats(), PoolStats { hits: 8, misses: 2 });
+
+            queue.reset_stats();
+
+            assert_eq!(queue.stats(), PoolStats::default());
+        }
+
+        #[test]
+        #[should_panic]
+        fn prewarm_beyond_pool_capacity() {
+            PooledSharedLinkedQueue::<i32>::with_prewarm(8, 16);
+        }
+
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let mut queue = PooledSharedLinkedQueue::with_prewarm(4, 4);
+
+                for _ in 0..10 {
+                    queue.enqueue(item.clone());
+                }
+
+                queue.deque();
+            }
+
+            assert_eq!(Rc::strong_count(&item), 1);
+        }
+
+        #[test]
+        fn iter_over_items() {
+            let mut queue = PooledSharedLinkedQueue::new(16);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 10..15 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..15).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 10);
+        }
+
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = PooledSharedLinkedQueue::new(16);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            for item in &mut queue {
+                *item *= 2;
+            }
+
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
+        }
+
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = PooledSharedLinkedQueue::new(16);
+
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..10).collect::<Vec<i32>>());
+        }
+
+        #[test]
+        fn collect_into_queue() {
+            let mut queue = (0..10).collect::<PooledSharedLinkedQueue<i32>>();
+            let mut items = vec![];
+
+            queue.extend(10..20);
+            queue.enqueue_slice(&[20, 21]);
+
+            assert_eq!(queue.deque_into(&mut items, 30), 22);
+            assert_eq!(items, (0..22).collect::<Vec<i32>>());
+        }
     }
 }
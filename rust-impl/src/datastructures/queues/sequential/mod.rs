@@ -5,33 +5,60 @@ extern crate alloc;
 
 use self::alloc::raw_vec::RawVec;
 
+use std::iter::FromIterator;
+use std::marker::PhantomData;
 use std::ptr::{self, Shared};
 use std::rc::Rc;
 use std::cell::RefCell;
 
-use super::Queue;
+use super::{IntoIter, Queue};
 
 type RcRefCellLink<T> = Option<Rc<RefCell<T>>>;
 type SharedLink<T> = Option<Shared<T>>;
 
-pub struct RcRefCellLinkLinkedArrayQueue {
+const DEFAULT_SEGMENT_CAPACITY: usize = 256;
+// indexes are bumped before an item is written, so the first slot of a segment never holds one
+pub const MIN_SEGMENT_CAPACITY: usize = 2;
+
+pub struct RcRefCellLinkLinkedArrayQueue<T> {
     segment_capacity: usize,
-    head: RcRefCellLink<RcRefCellSegment>,
-    tail: RcRefCellLink<RcRefCellSegment>
+    head: RcRefCellLink<RcRefCellSegment<T>>,
+    tail: RcRefCellLink<RcRefCellSegment<T>>,
+    size: usize
 }
 
-impl RcRefCellLinkLinkedArrayQueue {
-    pub fn new(segment_capacity: usize) -> RcRefCellLinkLinkedArrayQueue {
+impl <T> RcRefCellLinkLinkedArrayQueue<T> {
+    pub fn new(segment_capacity: usize) -> RcRefCellLinkLinkedArrayQueue<T> {
+        assert!(segment_capacity >= MIN_SEGMENT_CAPACITY, "segment capacity must be at least {}, got {}", MIN_SEGMENT_CAPACITY, segment_capacity);
         RcRefCellLinkLinkedArrayQueue {
             segment_capacity: segment_capacity,
             head: None,
-            tail: None
+            tail: None,
+            size: 0
+        }
+    }
+
+    pub fn iter(&self) -> RcRefCellLinkLinkedArrayIter<T> {
+        RcRefCellLinkLinkedArrayIter {
+            segment: self.head.as_ref().map_or(ptr::null_mut(), |head| head.as_ptr()),
+            index: self.head.as_ref().map_or(0, |head| unsafe { (*head.as_ptr()).first + 1 }),
+            remaining: self.size,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> RcRefCellLinkLinkedArrayIterMut<T> {
+        RcRefCellLinkLinkedArrayIterMut {
+            segment: self.head.as_ref().map_or(ptr::null_mut(), |head| head.as_ptr()),
+            index: self.head.as_ref().map_or(0, |head| unsafe { (*head.as_ptr()).first + 1 }),
+            remaining: self.size,
+            _marker: PhantomData
         }
     }
 }
 
-impl Queue<i32> for RcRefCellLinkLinkedArrayQueue {
-    fn deque(&mut self) -> Option<i32> {
+impl <T> Queue<T> for RcRefCellLinkLinkedArrayQueue<T> {
+    fn deque(&mut self) -> Option<T> {
         self.head.take().and_then(
             |head| {
                 if head.borrow().is_empty() && self.tail.as_ref().map_or(true, |tail| tail == &head) {
@@ -47,10 +74,13 @@ impl Queue<i32> for RcRefCellLinkLinkedArrayQueue {
                     Some(head.borrow_mut().read_first())
                 }
             }
-        )
+        ).map(|item| {
+            self.size -= 1;
+            item
+        })
     }
 
-    fn enqueue(&mut self, item: i32) {
+    fn enqueue(&mut self, item: T) {
         match self.tail.take() {
             Some(tail) => {
                 if tail.borrow().is_full() {
@@ -68,24 +98,175 @@ impl Queue<i32> for RcRefCellLinkLinkedArrayQueue {
             }
         }
         self.tail.as_ref().map(|tail| tail.borrow_mut().write_last(item));
+        self.size += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.head.as_ref().map(|head| unsafe {
+                let mut segment = head.as_ptr();
+                if (*segment).is_exhausted() {
+                    segment = (*segment).next.as_ref().unwrap().as_ptr();
+                }
+                &*(*segment).first_item()
+            })
+        }
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.head.as_ref().map(|head| unsafe {
+                let mut segment = head.as_ptr();
+                if (*segment).is_exhausted() {
+                    segment = (*segment).next.as_ref().unwrap().as_ptr();
+                }
+                &mut *(*segment).first_item()
+            })
+        }
+    }
+}
+
+impl <T> Drop for RcRefCellLinkLinkedArrayQueue<T> {
+    fn drop(&mut self) {
+        let mut segment = self.head.take();
+        while let Some(current) = segment {
+            segment = current.borrow_mut().next.take();
+        }
+    }
+}
+
+impl <T> FromIterator<T> for RcRefCellLinkLinkedArrayQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut queue = RcRefCellLinkLinkedArrayQueue::new(DEFAULT_SEGMENT_CAPACITY);
+        queue.enqueue_all(items);
+        queue
+    }
+}
+
+impl <T> Extend<T> for RcRefCellLinkLinkedArrayQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <T> IntoIterator for RcRefCellLinkLinkedArrayQueue<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T, RcRefCellLinkLinkedArrayQueue<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, T> IntoIterator for &'a RcRefCellLinkLinkedArrayQueue<T> {
+    type Item = &'a T;
+    type IntoIter = RcRefCellLinkLinkedArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl <'a, T> IntoIterator for &'a mut RcRefCellLinkLinkedArrayQueue<T> {
+    type Item = &'a mut T;
+    type IntoIter = RcRefCellLinkLinkedArrayIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct RcRefCellLinkLinkedArrayIter<'a, T: 'a> {
+    segment: *mut RcRefCellSegment<T>,
+    index: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a T>
+}
+
+impl <'a, T> Iterator for RcRefCellLinkLinkedArrayIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            None
+        } else {
+            unsafe {
+                if self.index > (*self.segment).last {
+                    self.segment = (*self.segment).next.as_ref().unwrap().as_ptr();
+                    self.index = (*self.segment).first + 1;
+                }
+                let item = &*(*self.segment).items.ptr().offset(self.index as isize);
+                self.index += 1;
+                self.remaining -= 1;
+                Some(item)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl <'a, T> ExactSizeIterator for RcRefCellLinkLinkedArrayIter<'a, T> {}
+
+pub struct RcRefCellLinkLinkedArrayIterMut<'a, T: 'a> {
+    segment: *mut RcRefCellSegment<T>,
+    index: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a mut T>
+}
+
+impl <'a, T> Iterator for RcRefCellLinkLinkedArrayIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            None
+        } else {
+            unsafe {
+                if self.index > (*self.segment).last {
+                    self.segment = (*self.segment).next.as_ref().unwrap().as_ptr();
+                    self.index = (*self.segment).first + 1;
+                }
+                let item = &mut *(*self.segment).items.ptr().offset(self.index as isize);
+                self.index += 1;
+                self.remaining -= 1;
+                Some(item)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
-struct RcRefCellSegment {
-    items: RawVec<i32>,
-    next: RcRefCellLink<RcRefCellSegment>,
+impl <'a, T> ExactSizeIterator for RcRefCellLinkLinkedArrayIterMut<'a, T> {}
+
+struct RcRefCellSegment<T> {
+    items: RawVec<T>,
+    next: RcRefCellLink<RcRefCellSegment<T>>,
     first: usize,
     last: usize
 }
 
-impl PartialEq for RcRefCellSegment {
-    fn eq(&self, other: &RcRefCellSegment) -> bool {
+impl <T> PartialEq for RcRefCellSegment<T> {
+    fn eq(&self, other: &RcRefCellSegment<T>) -> bool {
         self.items.ptr() == other.items.ptr()
     }
 }
 
-impl RcRefCellSegment {
-    fn new(capacity: usize) -> Rc<RefCell<RcRefCellSegment>> {
+impl <T> RcRefCellSegment<T> {
+    fn new(capacity: usize) -> Rc<RefCell<RcRefCellSegment<T>>> {
         Rc::new(
             RefCell::new(
                 RcRefCellSegment {
@@ -98,7 +279,7 @@ impl RcRefCellSegment {
         )
     }
 
-    fn read_first(&mut self) -> i32 {
+    fn read_first(&mut self) -> T {
         self.first += 1;
         unsafe {
             let index_to_read = self.items.ptr().offset(self.first as isize);
@@ -106,7 +287,7 @@ impl RcRefCellSegment {
         }
     }
 
-    fn write_last(&mut self, item: i32) {
+    fn write_last(&mut self, item: T) {
         self.last += 1;
         unsafe {
             let index_to_write = self.items.ptr().offset(self.last as isize);
@@ -114,6 +295,10 @@ impl RcRefCellSegment {
         }
     }
 
+    fn first_item(&self) -> *mut T {
+        unsafe { self.items.ptr().offset((self.first + 1) as isize) }
+    }
+
     fn is_empty(&self) -> bool {
         self.first == self.last
     }
@@ -127,24 +312,107 @@ impl RcRefCellSegment {
     }
 }
 
-pub struct SharedLinkLinkedArrayQueue {
+impl <T> Drop for RcRefCellSegment<T> {
+    fn drop(&mut self) {
+        for index in (self.first + 1)..(self.last + 1) {
+            unsafe {
+                ptr::drop_in_place(self.items.ptr().offset(index as isize));
+            }
+        }
+    }
+}
+
+const SPARE_SEGMENTS_LIMIT: usize = 4;
+
+pub struct SharedLinkLinkedArrayQueue<T> {
     segment_capacity: usize,
-    head: SharedLink<SharedSegment>,
-    tail: SharedLink<SharedSegment>
+    head: SharedLink<SharedSegment<T>>,
+    tail: SharedLink<SharedSegment<T>>,
+    size: usize,
+    spares: SharedLink<SharedSegment<T>>,
+    spare_segments: usize,
+    spare_segments_limit: usize
 }
 
-impl SharedLinkLinkedArrayQueue {
-    pub fn new(segment_capacity: usize) -> SharedLinkLinkedArrayQueue {
+impl <T> SharedLinkLinkedArrayQueue<T> {
+    pub fn new(segment_capacity: usize) -> SharedLinkLinkedArrayQueue<T> {
+        SharedLinkLinkedArrayQueue::with_spare_segments_limit(segment_capacity, SPARE_SEGMENTS_LIMIT)
+    }
+
+    pub fn with_spare_segments_limit(segment_capacity: usize, spare_segments_limit: usize) -> SharedLinkLinkedArrayQueue<T> {
+        assert!(segment_capacity >= MIN_SEGMENT_CAPACITY, "segment capacity must be at least {}, got {}", MIN_SEGMENT_CAPACITY, segment_capacity);
         SharedLinkLinkedArrayQueue {
             segment_capacity: segment_capacity,
             head: None,
-            tail: None
+            tail: None,
+            size: 0,
+            spares: None,
+            spare_segments: 0,
+            spare_segments_limit: spare_segments_limit
+        }
+    }
+
+    pub fn spare_segments(&self) -> usize {
+        self.spare_segments
+    }
+
+    pub fn iter(&self) -> SharedLinkLinkedArrayIter<T> {
+        SharedLinkLinkedArrayIter {
+            segment: self.head.as_ref().map_or(ptr::null_mut(), |head| head.as_ptr()),
+            index: self.head.as_ref().map_or(0, |head| unsafe { (*head.as_ptr()).first + 1 }),
+            remaining: self.size,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> SharedLinkLinkedArrayIterMut<T> {
+        SharedLinkLinkedArrayIterMut {
+            segment: self.head.as_ref().map_or(ptr::null_mut(), |head| head.as_ptr()),
+            index: self.head.as_ref().map_or(0, |head| unsafe { (*head.as_ptr()).first + 1 }),
+            remaining: self.size,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        let mut segment = self.spares.take();
+        while let Some(current) = segment {
+            unsafe {
+                segment = current.as_ref().next;
+                drop(Box::from_raw(current.as_ptr()));
+            }
+        }
+        self.spare_segments = 0;
+    }
+
+    fn acquire_segment(&mut self) -> SharedLink<SharedSegment<T>> {
+        match self.spares.take() {
+            Some(mut segment) => {
+                self.spares = unsafe { segment.as_mut().next.take() };
+                self.spare_segments -= 1;
+                Some(segment)
+            }
+            None => SharedSegment::new(self.segment_capacity)
+        }
+    }
+
+    // only exhausted segments are recycled, they do not hold any items
+    fn recycle_segment(&mut self, mut segment: Shared<SharedSegment<T>>) {
+        unsafe {
+            if self.spare_segments < self.spare_segments_limit {
+                segment.as_mut().reset();
+                segment.as_mut().next = self.spares.take();
+                self.spares = Some(segment);
+                self.spare_segments += 1;
+            } else {
+                drop(Box::from_raw(segment.as_ptr()));
+            }
         }
     }
 }
 
-impl Queue<i32> for SharedLinkLinkedArrayQueue {
-    fn deque(&mut self) -> Option<i32> {
+impl <T> Queue<T> for SharedLinkLinkedArrayQueue<T> {
+    fn deque(&mut self) -> Option<T> {
         self.head.take().and_then(
             |mut head| unsafe {
                 if head.as_ref().is_empty() && self.tail.as_ref().map_or(true, |tail| tail.as_ptr() == head.as_ptr()) {
@@ -155,22 +423,25 @@ impl Queue<i32> for SharedLinkLinkedArrayQueue {
                         self.head = Some(next.clone());
                         next.as_mut().read_first()
                     });
-                    drop(Box::from_raw(head.as_ptr()));
+                    self.recycle_segment(head);
                     ret
                 } else {
                     self.head = Some(head.clone());
                     Some(head.as_mut().read_first())
                 }
             }
-        )
+        ).map(|item| {
+            self.size -= 1;
+            item
+        })
     }
 
-    fn enqueue(&mut self, item: i32) {
+    fn enqueue(&mut self, item: T) {
         unsafe {
             match self.tail.take() {
                 Some(mut tail) => {
                     if tail.as_ref().is_full() {
-                        let segment = SharedSegment::new(self.segment_capacity);
+                        let segment = self.acquire_segment();
                         tail.as_mut().next = segment.clone();
                         self.tail = segment;
                     } else {
@@ -178,37 +449,186 @@ impl Queue<i32> for SharedLinkLinkedArrayQueue {
                     }
                 }
                 None => {
-                    let segment = SharedSegment::new(self.segment_capacity);
+                    let segment = self.acquire_segment();
                     self.head = segment.clone();
                     self.tail = segment;
                 }
             }
             self.tail.as_mut().map(|tail| tail.as_mut().write_last(item));
         }
+        self.size += 1;
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.head.map(|head| unsafe {
+                let mut segment = head.as_ptr();
+                if (*segment).is_exhausted() {
+                    segment = (*segment).next.unwrap().as_ptr();
+                }
+                &*(*segment).first_item()
+            })
+        }
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.head.map(|head| unsafe {
+                let mut segment = head.as_ptr();
+                if (*segment).is_exhausted() {
+                    segment = (*segment).next.unwrap().as_ptr();
+                }
+                &mut *(*segment).first_item()
+            })
+        }
     }
 }
 
-impl Drop for SharedLinkLinkedArrayQueue {
+impl <T> Drop for SharedLinkLinkedArrayQueue<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.deque() {}
+        self.shrink_to_fit();
+        let mut segment = self.head.take();
+        while let Some(current) = segment {
+            unsafe {
+                segment = current.as_ref().next;
+                drop(Box::from_raw(current.as_ptr()));
+            }
+        }
+    }
+}
+
+impl <T> FromIterator<T> for SharedLinkLinkedArrayQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut queue = SharedLinkLinkedArrayQueue::new(DEFAULT_SEGMENT_CAPACITY);
+        queue.enqueue_all(items);
+        queue
+    }
+}
+
+impl <T> Extend<T> for SharedLinkLinkedArrayQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        self.enqueue_all(items);
+    }
+}
+
+impl <T> IntoIterator for SharedLinkLinkedArrayQueue<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T, SharedLinkLinkedArrayQueue<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl <'a, T> IntoIterator for &'a SharedLinkLinkedArrayQueue<T> {
+    type Item = &'a T;
+    type IntoIter = SharedLinkLinkedArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl <'a, T> IntoIterator for &'a mut SharedLinkLinkedArrayQueue<T> {
+    type Item = &'a mut T;
+    type IntoIter = SharedLinkLinkedArrayIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct SharedLinkLinkedArrayIter<'a, T: 'a> {
+    segment: *mut SharedSegment<T>,
+    index: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a T>
+}
+
+impl <'a, T> Iterator for SharedLinkLinkedArrayIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            None
+        } else {
+            unsafe {
+                if self.index > (*self.segment).last {
+                    self.segment = (*self.segment).next.unwrap().as_ptr();
+                    self.index = (*self.segment).first + 1;
+                }
+                let item = &*(*self.segment).items.ptr().offset(self.index as isize);
+                self.index += 1;
+                self.remaining -= 1;
+                Some(item)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl <'a, T> ExactSizeIterator for SharedLinkLinkedArrayIter<'a, T> {}
+
+pub struct SharedLinkLinkedArrayIterMut<'a, T: 'a> {
+    segment: *mut SharedSegment<T>,
+    index: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a mut T>
+}
+
+impl <'a, T> Iterator for SharedLinkLinkedArrayIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            None
+        } else {
+            unsafe {
+                if self.index > (*self.segment).last {
+                    self.segment = (*self.segment).next.unwrap().as_ptr();
+                    self.index = (*self.segment).first + 1;
+                }
+                let item = &mut *(*self.segment).items.ptr().offset(self.index as isize);
+                self.index += 1;
+                self.remaining -= 1;
+                Some(item)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
-struct SharedSegment {
-    items: RawVec<i32>,
-    next: SharedLink<SharedSegment>,
+impl <'a, T> ExactSizeIterator for SharedLinkLinkedArrayIterMut<'a, T> {}
+
+struct SharedSegment<T> {
+    items: RawVec<T>,
+    next: SharedLink<SharedSegment<T>>,
     first: usize,
     last: usize
 }
 
-impl PartialEq for SharedSegment {
-    fn eq(&self, other: &SharedSegment) -> bool {
+impl <T> PartialEq for SharedSegment<T> {
+    fn eq(&self, other: &SharedSegment<T>) -> bool {
         self.items.ptr() == other.items.ptr()
     }
 }
 
-impl SharedSegment {
-    fn new(capacity: usize) -> SharedLink<SharedSegment> {
+impl <T> SharedSegment<T> {
+    fn new(capacity: usize) -> SharedLink<SharedSegment<T>> {
         Shared::new(
             Box::into_raw(Box::new(
                 SharedSegment {
@@ -221,7 +641,7 @@ impl SharedSegment {
         )
     }
 
-    fn read_first(&mut self) -> i32 {
+    fn read_first(&mut self) -> T {
         self.first += 1;
         unsafe {
             let index_to_read = self.items.ptr().offset(self.first as isize);
@@ -229,7 +649,7 @@ impl SharedSegment {
         }
     }
 
-    fn write_last(&mut self, item: i32) {
+    fn write_last(&mut self, item: T) {
         self.last += 1;
         unsafe {
             let index_to_write = self.items.ptr().offset(self.last as isize);
@@ -237,6 +657,15 @@ impl SharedSegment {
         }
     }
 
+    fn first_item(&self) -> *mut T {
+        unsafe { self.items.ptr().offset((self.first + 1) as isize) }
+    }
+
+    fn reset(&mut self) {
+        self.first = 0;
+        self.last = 0;
+    }
+
     fn is_empty(&self) -> bool {
         self.first == self.last
     }
@@ -250,6 +679,16 @@ impl SharedSegment {
     }
 }
 
+impl <T> Drop for SharedSegment<T> {
+    fn drop(&mut self) {
+        for index in (self.first + 1)..(self.last + 1) {
+            unsafe {
+                ptr::drop_in_place(self.items.ptr().offset(index as isize));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod rc_ref_cell_link_linked_array_queue {
@@ -257,8 +696,27 @@ mod tests {
 
         #[test]
         fn deque_from_empty_queue() {
-            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
+            let mut queue: RcRefCellLinkLinkedArrayQueue<i32> = RcRefCellLinkLinkedArrayQueue::new(16);
+
+            assert_eq!(queue.deque(), None);
+        }
+
+        #[test]
+        #[should_panic]
+        fn segment_without_room_for_an_item() {
+            RcRefCellLinkLinkedArrayQueue::<i32>::new(1);
+        }
+
+        #[test]
+        fn smallest_segments() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(MIN_SEGMENT_CAPACITY);
 
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i));
+            }
             assert_eq!(queue.deque(), None);
         }
 
@@ -339,1487 +797,494 @@ mod tests {
                 assert_eq!(queue.deque(), None);
             }
         }
-    }
-
-    mod shared_link_linked_array_queue {
-        use super::super::*;
 
         #[test]
-        fn deque_from_empty_queue() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+        fn len_of_queue() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
-            assert_eq!(queue.deque(), None);
-        }
+            assert_eq!(queue.len(), 0);
+            assert!(queue.is_empty());
 
-        #[test]
-        fn enqueue_deque_item() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+            for i in 0..20 {
+                queue.enqueue(i);
+            }
 
-            queue.enqueue(10);
+            assert_eq!(queue.len(), 20);
+            assert!(!queue.is_empty());
 
-            assert_eq!(queue.deque(), Some(10));
-            assert_eq!(queue.deque(), None);
+            queue.deque();
+
+            assert_eq!(queue.len(), 19);
         }
 
         #[test]
-        fn enqueue_deque_item_many_times() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
-            queue.enqueue(10);
-
-            assert_eq!(queue.deque(), Some(10));
-
-            queue.enqueue(20);
-
-            assert_eq!(queue.deque(), Some(20));
-
-            queue.enqueue(30);
+        fn peek_from_empty_queue() {
+            let mut queue: RcRefCellLinkLinkedArrayQueue<i32> = RcRefCellLinkLinkedArrayQueue::new(16);
 
-            assert_eq!(queue.deque(), Some(30));
-            assert_eq!(queue.deque(), None);
+            assert_eq!(queue.peek(), None);
+            assert_eq!(queue.peek_mut(), None);
         }
 
         #[test]
-        fn enqueue_many_items_deque_all() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
-            queue.enqueue(10);
-            queue.enqueue(20);
-            queue.enqueue(30);
+        fn peek_items_across_segments() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
-            assert_eq!(queue.deque(), Some(10));
-            assert_eq!(queue.deque(), Some(20));
-            assert_eq!(queue.deque(), Some(30));
-            assert_eq!(queue.deque(), None);
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+
+            for i in 0..40 {
+                assert_eq!(queue.peek(), Some(&i));
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.peek(), None);
         }
 
         #[test]
-        fn enqueue_deque_items_more_than_segment_capacity() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+        fn peek_mut_changes_first_item() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
+
             for i in 0..20 {
                 queue.enqueue(i);
             }
-
-            for i in 0..20 {
+            for i in 0..15 {
                 assert_eq!(queue.deque(), Some(i));
             }
-            assert_eq!(queue.deque(), None);
+
+            queue.peek_mut().map(|item| *item = 100);
+
+            assert_eq!(queue.deque(), Some(100));
+            assert_eq!(queue.deque(), Some(16));
         }
 
         #[test]
-        fn enqueue_more_than_one_segment_then_deque() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+        fn enqueue_deque_non_copy_items() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
             for i in 0..40 {
-                queue.enqueue(i);
+                queue.enqueue(i.to_string());
             }
 
             for i in 0..40 {
-                assert_eq!(queue.deque(), Some(i));
+                assert_eq!(queue.deque(), Some(i.to_string()));
             }
             assert_eq!(queue.deque(), None);
         }
 
         #[test]
-        fn enqueue_deque_more_than_segment_capacity() {
-            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
-            for i in 0..40 {
-                queue.enqueue(i);
-                assert_eq!(queue.deque(), Some(i));
-                assert_eq!(queue.deque(), None);
-            }
-        }
-    }
-}
-
-#[cfg(test)]
-mod benchmarks {
-    extern crate test;
-
-    use super::Queue;
-
-    const K: usize = 1024;
-    const _2_K: usize = 2 * K;
-    const _4_K: usize = 4 * K;
-    const _8_K: usize = 8 * K;
-    const _16_K: usize = 16 * K;
-    const _32_K: usize = 32 * K;
-    const _64_K: usize = 64 * K;
-    const _128_K: usize = 128 * K;
-    const _256_K: usize = 256 * K;
-    const _512_K: usize = 512 * K;
-    const M: usize = K * K;
-    const _2_M: usize = 2 * M;
-    const _4_M: usize = 4 * M;
-    const _8_M: usize = 8 * M;
-    const _16_M: usize = 16 * M;
-    const _32_M: usize = 32 * M;
-
-    mod baselines {
-        use super::test::Bencher;
-        use super::*;
-        use std::collections::LinkedList;
-
-        #[bench]
-        fn linked_list(b: &mut Bencher) {
-            let mut list = LinkedList::new();
-            b.iter(|| {
-                for item in 0..K {
-                    list.push_back(item);
+                for _ in 0..40 {
+                    queue.enqueue(item.clone());
                 }
-                let mut sum = 0;
-                while let Some(item) = list.pop_front() {
-                    sum += item;
+
+                for _ in 0..20 {
+                    queue.deque();
                 }
-                sum
-            });
-        }
-        #[bench]
-        fn linked_list_iter(b: &mut Bencher) {
-            b.iter(|| {
-                let mut s: usize = 0;
-                s = (0..K).collect::<LinkedList<usize>>().iter().sum();
-                s
-            });
-        }
-    }
+            }
 
-    mod std_resizable_array_queue {
-        use super::test::Bencher;
-        use super::*;
-        use std::collections::VecDeque;
-
-        #[bench]
-        fn size_0001k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(K);
-                enqueue_many_std(&mut queue, K);
-                deque_many_std(&mut queue)
-            })
+            assert_eq!(Rc::strong_count(&item), 1);
         }
 
-        #[bench]
-        fn size_0002k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_2_K);
-                enqueue_many_std(&mut queue, _2_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0004k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_4_K);
-                enqueue_many_std(&mut queue, _4_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0008k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_8_K);
-                enqueue_many_std(&mut queue, _8_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0016k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_16_K);
-                enqueue_many_std(&mut queue, _16_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0032k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_32_K);
-                enqueue_many_std(&mut queue, _32_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0064k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_64_K);
-                enqueue_many_std(&mut queue, _64_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0128k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_128_K);
-                enqueue_many_std(&mut queue, _128_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0256k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_256_K);
-                enqueue_many_std(&mut queue, _256_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0512k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(_512_K);
-                enqueue_many_std(&mut queue, _512_K);
-                deque_many_std(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_1024k(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = VecDeque::with_capacity(M);
-                enqueue_many_std(&mut queue, M);
-                deque_many_std(&mut queue)
-            })
-        }
+        #[test]
+        fn iter_over_items() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
-        fn enqueue_many_std(queue: &mut VecDeque<i32>, size: usize) {
-            for item in 0..size {
-                queue.push_back(item as i32);
+            for i in 0..40 {
+                queue.enqueue(i);
             }
-        }
-
-        fn deque_many_std(queue: &mut VecDeque<i32>) -> i32 {
-            let mut sum = 0;
-            while let Some(item) = queue.pop_front() {
-                sum += item;
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 40..45 {
+                queue.enqueue(i);
             }
-            sum
-        }
-    }
-
-    const SEGMENT_16_SIZE: usize = 16;
-    const SEGMENT_32_SIZE: usize = 32;
-    const SEGMENT_64_SIZE: usize = 64;
-    const SEGMENT_128_SIZE: usize = 128;
-    const SEGMENT_256_SIZE: usize = 256;
-    const SEGMENT_512_SIZE: usize = 512;
-
-    mod rc_ref_cell_linked_array_queue {
-        use super::test::Bencher;
-        use super::super::RcRefCellLinkLinkedArrayQueue;
-        use super::*;
-
-        #[bench]
-        fn size_0001k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0002k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0004k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0008k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0016k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0032k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0064k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0128k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0256k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0512k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_1024k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0001k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0002k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0004k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0008k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0016k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0032k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0064k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0128k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0256k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0512k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_1024k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0001k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0002k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0004k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0008k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0016k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0032k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0064k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0128k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0256k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0512k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_1024k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0001k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0002k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0004k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0008k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0016k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0032k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0064k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0128k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0256k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0512k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_1024k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0001k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
-
-        #[bench]
-        fn size_0002k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
 
-        #[bench]
-        fn size_0004k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..45).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 40);
         }
 
-        #[bench]
-        fn size_0008k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0016k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0032k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
+            for item in &mut queue {
+                *item *= 2;
+            }
 
-        #[bench]
-        fn size_0064k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..40 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
         }
 
-        #[bench]
-        fn size_0128k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = RcRefCellLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0256k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0512k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..40).collect::<Vec<i32>>());
         }
 
-        #[bench]
-        fn size_1024k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn collect_into_queue() {
+            let mut queue = (0..300).collect::<RcRefCellLinkLinkedArrayQueue<i32>>();
+            let mut items = vec![];
 
-        #[bench]
-        fn size_0001k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.extend(300..600);
 
-        #[bench]
-        fn size_0002k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.deque_into(&mut items, 1000), 600);
+            assert_eq!(items, (0..600).collect::<Vec<i32>>());
         }
+    }
 
-        #[bench]
-        fn size_0004k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+    mod shared_link_linked_array_queue {
+        use super::super::*;
 
-        #[bench]
-        fn size_0008k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn deque_from_empty_queue() {
+            let mut queue: SharedLinkLinkedArrayQueue<i32> = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0016k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_0032k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
+        #[test]
+        #[should_panic]
+        fn segment_without_room_for_an_item() {
+            SharedLinkLinkedArrayQueue::<i32>::new(1);
         }
 
-        #[bench]
-        fn size_0064k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn smallest_segments() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(MIN_SEGMENT_CAPACITY);
 
-        #[bench]
-        fn size_0128k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..10 {
+                queue.enqueue(i);
+            }
+            for i in 0..10 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_0256k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_deque_item() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0512k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.enqueue(10);
 
-        #[bench]
-        fn size_1024k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = RcRefCellLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), None);
         }
-    }
 
-    mod shared_linked_array_queue {
-        use super::test::Bencher;
-        use super::super::SharedLinkLinkedArrayQueue;
-        use super::*;
-
-        #[bench]
-        fn size_0001k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_deque_item_many_times() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+            queue.enqueue(10);
 
-        #[bench]
-        fn size_0002k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
+            assert_eq!(queue.deque(), Some(10));
 
-        #[bench]
-        fn size_0004k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.enqueue(20);
 
-        #[bench]
-        fn size_0008k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
+            assert_eq!(queue.deque(), Some(20));
 
-        #[bench]
-        fn size_0016k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.enqueue(30);
 
-        #[bench]
-        fn size_0032k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_0064k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_many_items_deque_all() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+            queue.enqueue(10);
+            queue.enqueue(20);
+            queue.enqueue(30);
 
-        #[bench]
-        fn size_0128k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.deque(), Some(10));
+            assert_eq!(queue.deque(), Some(20));
+            assert_eq!(queue.deque(), Some(30));
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_0256k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_deque_items_more_than_segment_capacity() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
+            for i in 0..20 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0512k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..20 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_1024k_segment_size_0016(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_16_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_more_than_one_segment_then_deque() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0001k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0002k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..40 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_0004k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_deque_more_than_segment_capacity() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0008k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..40 {
+                queue.enqueue(i);
+                assert_eq!(queue.deque(), Some(i));
+                assert_eq!(queue.deque(), None);
+            }
         }
 
-        #[bench]
-        fn size_0016k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn len_of_queue() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0032k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
+            assert_eq!(queue.len(), 0);
+            assert!(queue.is_empty());
 
-        #[bench]
-        fn size_0064k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..20 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0128k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
+            assert_eq!(queue.len(), 20);
+            assert!(!queue.is_empty());
 
-        #[bench]
-        fn size_0256k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.deque();
 
-        #[bench]
-        fn size_0512k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.len(), 19);
         }
 
-        #[bench]
-        fn size_1024k_segment_size_0032(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_32_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn peek_from_empty_queue() {
+            let mut queue: SharedLinkLinkedArrayQueue<i32> = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0001k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.peek(), None);
+            assert_eq!(queue.peek_mut(), None);
         }
 
-        #[bench]
-        fn size_0002k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn peek_items_across_segments() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0004k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0008k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..40 {
+                assert_eq!(queue.peek(), Some(&i));
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.peek(), None);
         }
 
-        #[bench]
-        fn size_0016k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn peek_mut_changes_first_item() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0032k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..20 {
+                queue.enqueue(i);
+            }
+            for i in 0..15 {
+                assert_eq!(queue.deque(), Some(i));
+            }
 
-        #[bench]
-        fn size_0064k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.peek_mut().map(|item| *item = 100);
 
-        #[bench]
-        fn size_0128k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.deque(), Some(100));
+            assert_eq!(queue.deque(), Some(16));
         }
 
-        #[bench]
-        fn size_0256k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_deque_non_copy_items() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0512k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i.to_string());
+            }
 
-        #[bench]
-        fn size_1024k_segment_size_0064(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_64_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
+            for i in 0..40 {
+                assert_eq!(queue.deque(), Some(i.to_string()));
+            }
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_0001k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn drop_queue_with_remaining_items() {
+            let item = Rc::new(0);
+            {
+                let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0002k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
+                for _ in 0..40 {
+                    queue.enqueue(item.clone());
+                }
 
-        #[bench]
-        fn size_0004k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+                for _ in 0..20 {
+                    queue.deque();
+                }
+            }
 
-        #[bench]
-        fn size_0008k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(Rc::strong_count(&item), 1);
         }
 
-        #[bench]
-        fn size_0016k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn recycle_exhausted_segments_up_to_limit() {
+            let mut queue = SharedLinkLinkedArrayQueue::with_spare_segments_limit(16, 2);
 
-        #[bench]
-        fn size_0032k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..60 {
+                queue.enqueue(i);
+            }
+            for i in 0..60 {
+                assert_eq!(queue.deque(), Some(i));
+            }
 
-        #[bench]
-        fn size_0064k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
+            assert_eq!(queue.spare_segments(), 2);
 
-        #[bench]
-        fn size_0128k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..30 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0256k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+            assert_eq!(queue.spare_segments(), 0);
 
-        #[bench]
-        fn size_0512k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
+            for i in 0..30 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            assert_eq!(queue.deque(), None);
         }
 
-        #[bench]
-        fn size_1024k_segment_size_0128(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_128_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn enqueue_deque_many_times_with_recycled_segments() {
+            let mut queue = SharedLinkLinkedArrayQueue::with_spare_segments_limit(16, 1);
 
-        #[bench]
-        fn size_0001k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
+            for round in 0..10 {
+                for i in 0..50 {
+                    queue.enqueue(round * 50 + i);
+                }
+                for i in 0..50 {
+                    assert_eq!(queue.deque(), Some(round * 50 + i));
+                }
+            }
+            assert_eq!(queue.deque(), None);
+            assert_eq!(queue.spare_segments(), 1);
         }
 
-        #[bench]
-        fn size_0002k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn shrink_to_fit_releases_spare_segments() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0004k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..60 {
+                queue.enqueue(i);
+            }
+            for i in 0..30 {
+                assert_eq!(queue.deque(), Some(i));
+            }
 
-        #[bench]
-        fn size_0008k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
-        }
+            queue.shrink_to_fit();
 
-        #[bench]
-        fn size_0016k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.spare_segments(), 0);
+            for i in 30..60 {
+                assert_eq!(queue.deque(), Some(i));
+            }
         }
 
-        #[bench]
-        fn size_0032k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn iter_over_items() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0064k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
+            for i in 0..5 {
+                assert_eq!(queue.deque(), Some(i));
+            }
+            for i in 40..45 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0128k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (5..45).collect::<Vec<i32>>());
+            assert_eq!(queue.len(), 40);
         }
 
-        #[bench]
-        fn size_0256k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn iter_mut_over_items() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0512k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_1024k_segment_size_0256(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_256_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
+            for item in &mut queue {
+                *item *= 2;
+            }
 
-        #[bench]
-        fn size_0001k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, K);
-                deque_many(&mut queue)
-            })
+            for i in 0..40 {
+                assert_eq!(queue.deque(), Some(i * 2));
+            }
         }
 
-        #[bench]
-        fn size_0002k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _2_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn into_iter_consumes_queue() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0004k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _4_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0008k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _8_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.into_iter().collect::<Vec<i32>>(), (0..40).collect::<Vec<i32>>());
         }
 
-        #[bench]
-        fn size_0016k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _16_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn drain_range_of_items() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0032k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _32_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0064k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _64_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.drain(5..10).collect::<Vec<i32>>(), vec![5, 6, 7, 8, 9]);
+            assert_eq!(queue.len(), 40 - 5);
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (0..5).chain(10..40).collect::<Vec<i32>>());
         }
 
-        #[bench]
-        fn size_0128k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _128_K);
-                deque_many(&mut queue)
-            })
-        }
+        #[test]
+        fn drop_partially_consumed_drain() {
+            let mut queue = SharedLinkLinkedArrayQueue::new(16);
 
-        #[bench]
-        fn size_0256k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _256_K);
-                deque_many(&mut queue)
-            })
-        }
+            for i in 0..40 {
+                queue.enqueue(i);
+            }
 
-        #[bench]
-        fn size_0512k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, _512_K);
-                deque_many(&mut queue)
-            })
+            assert_eq!(queue.drain(0..10).next(), Some(0));
+            assert_eq!(queue.iter().cloned().collect::<Vec<i32>>(), (10..40).collect::<Vec<i32>>());
         }
 
-        #[bench]
-        fn size_1024k_segment_size_0512(b: &mut Bencher) {
-            b.iter(|| {
-                let mut queue = SharedLinkLinkedArrayQueue::new(SEGMENT_512_SIZE);
-                enqueue_many(&mut queue, M);
-                deque_many(&mut queue)
-            })
-        }
-    }
+        #[test]
+        fn collect_into_queue() {
+            let mut queue = (0..300).collect::<SharedLinkLinkedArrayQueue<i32>>();
+            let mut items = vec![];
 
-    fn enqueue_many<Q: Queue<i32>>(queue: &mut Q, size: usize) {
-        for item in 0..size {
-            queue.enqueue(item as i32);
-        }
-    }
+            queue.extend(300..600);
 
-    fn deque_many<Q: Queue<i32>>(queue: &mut Q) -> i32 {
-        let mut sum = 0;
-        while let Some(item) = queue.deque() {
-            sum += item;
+            assert_eq!(queue.deque_into(&mut items, 1000), 600);
+            assert_eq!(items, (0..600).collect::<Vec<i32>>());
         }
-        sum
     }
 }